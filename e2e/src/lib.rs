@@ -0,0 +1,199 @@
+//! Helpers to run the built plugin through swc's plugin runner.
+//! The tests themselves live in `tests/e2e.rs`.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, OnceLock},
+};
+
+use serde_json::{json, Value};
+use swc_core::{
+    common::{
+        errors::HANDLER,
+        plugin::{metadata::TransformPluginMetadataContext, serialized::PluginSerializedBytes},
+        sync::Lazy,
+        FileName, Mark, SourceMap,
+    },
+    ecma::{
+        ast::{EsVersion, Program},
+        codegen::{text_writer::JsWriter, Emitter},
+        parser::{parse_file_as_program, Syntax, TsConfig},
+    },
+    plugin_runner::{cache::PluginModuleCache, create_plugin_transform_executor},
+    testing,
+};
+
+static CACHE: Lazy<PluginModuleCache> = Lazy::new(PluginModuleCache::new);
+
+/**
+ * Build the plugin once for all tests.
+ * The plugin runner embeds an older wasmer, so only MVP wasm features are enabled.
+ */
+pub fn plugin_path() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        if let Ok(path) = env::var("SWC_TEST_PLUGIN_WASM") {
+            return PathBuf::from(path);
+        }
+
+        let plugin_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let target_dir = plugin_dir.join("target").join("e2e");
+        // wasm32-wasi was renamed to wasm32-wasip1 in newer toolchains
+        for target in ["wasm32-wasip1", "wasm32-wasi"] {
+            let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+                .current_dir(plugin_dir)
+                .args(["build", "--lib", "--target", target, "--target-dir"])
+                .arg(&target_dir)
+                .env("RUSTFLAGS", "-C target-cpu=mvp")
+                .env_remove("CARGO_TARGET_DIR")
+                .status()
+                .expect("failed to run cargo");
+            if status.success() {
+                return target_dir
+                    .join(target)
+                    .join("debug")
+                    .join("swc_test_plugin.wasm");
+            }
+        }
+        panic!("failed to build the plugin, install the wasm32-wasip1 target");
+    })
+}
+
+pub struct PluginOutput {
+    pub code: String,
+    /// diagnostics emitted through the HANDLER of the plugin
+    pub stderr: String,
+}
+
+/**
+ * Run the built plugin over `src` with the Filename context and the plugin config
+ */
+pub fn run_plugin(
+    src: &str,
+    filename: Option<&str>,
+    plugin_config: Option<Value>,
+) -> Result<PluginOutput, String> {
+    run_plugin_with_env(src, filename, plugin_config, "development")
+}
+
+/**
+ * Same as run_plugin with the Env context (`envName` of swc)
+ */
+pub fn run_plugin_with_env(
+    src: &str,
+    filename: Option<&str>,
+    plugin_config: Option<Value>,
+    env: &str,
+) -> Result<PluginOutput, String> {
+    let mut code = None;
+    let result = testing::run_test(false, |cm, handler| {
+        let fm = cm.new_source_file(
+            filename.map_or(FileName::Anon, |f| FileName::Real(f.into())),
+            src.to_string(),
+        );
+        let program = parse_file_as_program(
+            &fm,
+            Syntax::Typescript(TsConfig {
+                tsx: true,
+                ..Default::default()
+            }),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+        let program = PluginSerializedBytes::try_serialize(&program).unwrap();
+
+        let transformed = HANDLER.set(handler, || {
+            let mut executor = create_plugin_transform_executor(
+                plugin_path(),
+                &CACHE,
+                &cm,
+                &Arc::new(TransformPluginMetadataContext::new(
+                    filename.map(str::to_string),
+                    env.to_string(),
+                    None,
+                )),
+                plugin_config,
+            )
+            .expect("failed to load the plugin");
+            executor.transform(&program, Mark::new(), false)
+        });
+        // the plugin panicked
+        let program: Program = match transformed {
+            Ok(bytes) => bytes.deserialize().unwrap(),
+            Err(err) => return Ok(Err(format!("{:?}", err))),
+        };
+
+        code = Some(print(cm, &program));
+        if handler.has_errors() {
+            return Err(());
+        }
+        Ok(Ok(()))
+    });
+
+    match result {
+        Ok(Ok(())) => Ok(PluginOutput {
+            code: code.unwrap(),
+            stderr: String::new(),
+        }),
+        Ok(Err(err)) => Err(err),
+        // diagnostics reported as errors
+        Err(stderr) => match code {
+            Some(code) => Ok(PluginOutput {
+                code,
+                stderr: stderr.to_string(),
+            }),
+            None => Err(stderr.to_string()),
+        },
+    }
+}
+
+fn print(cm: Arc<SourceMap>, program: &Program) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+        emitter.emit_program(program).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
+
+pub fn default_config() -> Value {
+    json!({
+        "attrName": "data-testid",
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+    })
+}
+
+// wasmer 2.x calls `__rust_probestack`, which is no longer exported by newer toolchains.
+// Same as the x86_64 stack probe of compiler-builtins: touch every page of the frame (size in rax).
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+std::arch::global_asm!(
+    ".globl __rust_probestack",
+    "__rust_probestack:",
+    "    push rbp",
+    "    mov rbp, rsp",
+    "    mov r11, rax",
+    "    cmp r11, 0x1000",
+    "    jna 3f",
+    "2:",
+    "    sub rsp, 0x1000",
+    "    test qword ptr [rsp + 8], rsp",
+    "    sub r11, 0x1000",
+    "    cmp r11, 0x1000",
+    "    ja 2b",
+    "3:",
+    "    sub rsp, r11",
+    "    test qword ptr [rsp + 8], rsp",
+    "    add rsp, rax",
+    "    leave",
+    "    ret",
+);
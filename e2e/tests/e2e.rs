@@ -0,0 +1,314 @@
+//! End-to-end tests
+//!
+//! Build the plugin as wasm and run `process_transform` through swc's plugin runner,
+//! in the same way as swc (or Next.js) does.
+//!
+//!   cd e2e && cargo test
+//!
+//! SWC_TEST_PLUGIN_WASM can point to a prebuilt .wasm to skip the build.
+
+use serde_json::json;
+use swc_test_plugin_e2e::{default_config, run_plugin, run_plugin_with_env};
+
+const COMPONENT: &str = r#"
+const UserCard = () => (
+  <div>name</div>
+)
+"#;
+
+#[test]
+fn transform_with_config() {
+    let output = run_plugin(
+        COMPONENT,
+        Some("/app/src/UserCard.tsx"),
+        Some(default_config()),
+    )
+    .unwrap();
+    assert!(
+        output.code.contains(r#"<div data-testid="user-card">"#),
+        "{}",
+        output.code
+    );
+}
+
+#[test]
+fn transform_with_preset() {
+    let output = run_plugin(
+        COMPONENT,
+        Some("/app/src/UserCard.tsx"),
+        Some(json!({
+            "preset": "react-native",
+            "ignoreFiles": [],
+            "ignoreComponents": [],
+        })),
+    )
+    .unwrap();
+    assert!(
+        output.code.contains(r#"<div testID="userCard">"#),
+        "{}",
+        output.code
+    );
+}
+
+#[test]
+fn transform_without_filename() {
+    let output = run_plugin(COMPONENT, None, Some(default_config())).unwrap();
+    assert!(
+        output.code.contains(r#"data-testid="user-card""#),
+        "{}",
+        output.code
+    );
+}
+
+#[test]
+fn ignore_files_by_filename() {
+    let config = json!({
+        "attrName": "data-testid",
+        "ignoreFiles": ["stories"],
+        "ignoreComponents": [],
+    });
+
+    let ignored = run_plugin(
+        COMPONENT,
+        Some("/app/src/stories/UserCard.tsx"),
+        Some(config.clone()),
+    )
+    .unwrap();
+    assert!(!ignored.code.contains("data-testid"), "{}", ignored.code);
+
+    let transformed = run_plugin(COMPONENT, Some("/app/src/UserCard.tsx"), Some(config)).unwrap();
+    assert!(
+        transformed.code.contains("data-testid"),
+        "{}",
+        transformed.code
+    );
+}
+
+#[test]
+fn ignore_components() {
+    let output = run_plugin(
+        COMPONENT,
+        Some("/app/src/UserCard.tsx"),
+        Some(json!({
+            "attrName": "data-testid",
+            "ignoreFiles": [],
+            "ignoreComponents": ["UserCard"],
+        })),
+    )
+    .unwrap();
+    assert!(!output.code.contains("data-testid"), "{}", output.code);
+}
+
+#[test]
+fn missing_config() {
+    // reported as an error, and the code is left as it is
+    let output = run_plugin(COMPONENT, Some("/app/src/UserCard.tsx"), None).unwrap();
+    assert!(
+        output.stderr.contains("the plugin has no config"),
+        "{}",
+        output.stderr
+    );
+    assert!(!output.code.contains("data-testid"), "{}", output.code);
+}
+
+#[test]
+fn invalid_config() {
+    // reported as an error, and the code is left as it is
+    let reported = |plugin_config: serde_json::Value, message: &str| {
+        let output = run_plugin(
+            COMPONENT,
+            Some("/app/src/UserCard.tsx"),
+            Some(plugin_config),
+        )
+        .unwrap();
+        assert!(output.stderr.contains(message), "{}", output.stderr);
+        assert!(!output.code.contains("data-testid"), "{}", output.code);
+    };
+
+    // ignoreFiles is required
+    reported(
+        json!({
+            "attrName": "data-testid",
+            "ignoreComponents": [],
+        }),
+        "missing field `ignoreFiles`",
+    );
+
+    reported(
+        json!({
+            "preset": "angular",
+            "ignoreFiles": [],
+            "ignoreComponents": [],
+        }),
+        "unknown variant `angular`",
+    );
+
+    reported(
+        json!({
+            "attrName": "data-testid",
+            "ignoreFiles": [],
+            "ignoreComponents": [],
+            "idTemplte": "qa-{component}",
+        }),
+        "unknown field `idTemplte`",
+    );
+}
+
+#[test]
+fn check_mode_reports_through_handler() {
+    let output = run_plugin(
+        COMPONENT,
+        Some("/app/src/UserCard.tsx"),
+        Some(json!({
+            "attrName": "data-testid",
+            "ignoreFiles": [],
+            "ignoreComponents": [],
+            "mode": "check",
+        })),
+    )
+    .unwrap();
+    assert!(!output.code.contains("data-testid"), "{}", output.code);
+    assert!(
+        output
+            .stderr
+            .contains("root element of `UserCard` has no data-testid"),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn profiles_by_env() {
+    let config = json!({
+        "attrName": "data-testid",
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "profiles": {
+            "development": { "sourceLocation": true },
+            "production": { "mode": "strip" },
+        },
+    });
+    let src = r#"
+const UserCard = () => (
+  <div data-testid="card"><img data-testid="avatar" /></div>
+)
+const Header = () => (
+  <header />
+)
+"#;
+
+    let development = run_plugin_with_env(
+        src,
+        Some("src/UserCard.tsx"),
+        Some(config.clone()),
+        "development",
+    )
+    .unwrap();
+    assert!(
+        development.code.contains(
+            r#"<header data-testid="header" data-source-location="src/UserCard.tsx:6:3"/>"#
+        ),
+        "{}",
+        development.code
+    );
+
+    let test =
+        run_plugin_with_env(src, Some("src/UserCard.tsx"), Some(config.clone()), "test").unwrap();
+    assert!(
+        test.code.contains(r#"<header data-testid="header"/>"#),
+        "{}",
+        test.code
+    );
+
+    let production =
+        run_plugin_with_env(src, Some("src/UserCard.tsx"), Some(config), "production").unwrap();
+    assert!(!production.code.contains("data-"), "{}", production.code);
+}
+
+#[test]
+fn paths_relative_to_cwd() {
+    // the host passes absolute paths, which differ between machines
+    let cwd = std::env::current_dir().unwrap();
+    let filename = cwd.join("app/users/[id]/page.tsx");
+    let config = json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "sourceLocation": true,
+        "routeIds": {},
+    });
+    let src = r#"
+export default function Page() {
+  return <main />;
+}
+"#;
+
+    let transformed = run_plugin(src, filename.to_str(), Some(config.clone())).unwrap();
+    assert!(
+        transformed.code.contains(
+            r#"<main data-testid="page-users-id" data-source-location="app/users/[id]/page.tsx:3:10"/>"#
+        ),
+        "{}",
+        transformed.code
+    );
+
+    // rootDir relative to cwd
+    let mut config = config;
+    config["rootDir"] = json!("app");
+    let transformed = run_plugin(src, filename.to_str(), Some(config)).unwrap();
+    assert!(
+        transformed
+            .code
+            .contains(r#"data-source-location="users/[id]/page.tsx:3:10""#),
+        "{}",
+        transformed.code
+    );
+}
+
+#[test]
+fn lockfile_in_cwd() {
+    // the plugin reads the lockfile through the /cwd mount of the wasm host
+    let path = "target/e2e-testids.lock.json";
+    std::fs::write(
+        path,
+        r#"{ "src/ProfileCard.tsx": { "UserCard": "user-card" } }"#,
+    )
+    .unwrap();
+    let config = json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "lockfile": path,
+        "updateLockfile": true,
+    });
+    let src = "export const ProfileCard = () => (<div />);";
+
+    let transformed = run_plugin(src, Some("src/ProfileCard.tsx"), Some(config.clone())).unwrap();
+    assert!(
+        transformed.code.contains(r#"data-testid="user-card""#),
+        "{}",
+        transformed.code
+    );
+    let lockfile: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(
+        lockfile,
+        json!({ "src/ProfileCard.tsx": { "ProfileCard": "user-card" } })
+    );
+
+    // a lock left over from a killed build is taken over
+    let lock = std::fs::File::create(format!("{}.lock", path)).unwrap();
+    lock.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(60))
+        .unwrap();
+    let transformed = run_plugin(src, Some("src/ProfileCard.tsx"), Some(config.clone())).unwrap();
+    assert!(transformed.stderr.is_empty(), "{}", transformed.stderr);
+    assert!(!std::path::Path::new(&format!("{}.lock", path)).exists());
+
+    // a lockfile with merge conflicts is reported, and the code is left as it is
+    std::fs::write(path, "<<<<<<< HEAD\n{}\n=======\n{}\n>>>>>>> main\n").unwrap();
+    let reported = run_plugin(src, Some("src/ProfileCard.tsx"), Some(config)).unwrap();
+    assert!(
+        reported.stderr.contains("invalid lockfile"),
+        "{}",
+        reported.stderr
+    );
+    assert!(!reported.code.contains("data-testid"), "{}", reported.code);
+}
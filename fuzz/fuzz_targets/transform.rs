@@ -0,0 +1,154 @@
+//! Run the visitor over arbitrary source text.
+//!
+//! The first byte chooses the preset and the mode, the rest is parsed as tsx.
+//! Inputs which don't parse (or don't parse again after printing) are skipped.
+//! The transform should never panic, never change code outside of JSX
+//! (and element calls such as createElement()), and the output should parse again.
+#![no_main]
+
+use std::path::Path;
+
+use libfuzzer_sys::fuzz_target;
+use swc_core::{
+    common::{EqIgnoreSpan, DUMMY_SP},
+    ecma::{
+        ast::{CallExpr, Callee, Expr, Lit, MemberExpr, MemberProp, Null},
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use swc_test_plugin::{
+    runner::{parse_source, print_program, transform_file, ParsedFile},
+    Config, Mode, Preset,
+};
+
+/**
+ * Replace every JSX element and fragment, and every call which may create an element, with `null`
+ */
+struct StripJsx;
+
+/**
+ * Functions which the visitor treats as element factories, e.g. React.createElement.
+ * Other member calls such as `doc.createElement()` are kept, so changes to them are caught.
+ */
+const FACTORIES: [&str; 8] = [
+    "createElement",
+    "React.createElement",
+    "jsx",
+    "jsxs",
+    "jsxDEV",
+    "_jsx",
+    "_jsxs",
+    "_jsxDEV",
+];
+
+fn callee_name(callee: &Expr) -> Option<String> {
+    match callee {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(prop),
+            ..
+        }) => Some(format!("{}.{}", callee_name(obj)?, prop.sym)),
+        Expr::Paren(paren) => callee_name(&paren.expr),
+        // (0, _jsxRuntime.jsx)(...)
+        Expr::Seq(seq) => match &**seq.exprs.last()? {
+            Expr::Member(MemberExpr {
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => Some(prop.sym.to_string()),
+            expr => callee_name(expr),
+        },
+        _ => None,
+    }
+}
+
+fn is_factory(callee: &Expr) -> bool {
+    callee_name(callee).is_some_and(|name| FACTORIES.contains(&name.as_str()))
+}
+
+impl VisitMut for StripJsx {
+    fn visit_mut_expr(&mut self, n: &mut Expr) {
+        let is_element = match n {
+            Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+            Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                ..
+            }) => is_factory(callee),
+            _ => false,
+        };
+        if is_element {
+            *n = Expr::Lit(Lit::Null(Null { span: DUMMY_SP }));
+            return;
+        }
+        n.visit_mut_children_with(self);
+    }
+}
+
+fn config(byte: u8) -> Config {
+    let preset = match byte % 5 {
+        0 => Preset::ReactDom,
+        1 => Preset::ReactNative,
+        2 => Preset::Preact,
+        3 => Preset::Solid,
+        _ => Preset::Vue,
+    };
+    let mode = if byte & 0x80 == 0 {
+        Mode::Transform
+    } else {
+        Mode::Check
+    };
+    Config {
+        preset,
+        mode,
+        ..Default::default()
+    }
+}
+
+/**
+ * Parse and print the input without the transform.
+ *
+ * The parser of this swc version panics on some inputs (e.g. `>>>>>>>`)
+ * and recovers from some errors silently (e.g. `<invalid>`),
+ * these inputs are not interesting for the visitor.
+ */
+fn parse_without_transform(path: &Path, src: &str) -> Option<ParsedFile> {
+    // libfuzzer aborts in its panic hook, so the hook is silenced while parsing
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let parsed = std::panic::catch_unwind(|| {
+        let file = parse_source(path, src.to_string()).ok()?;
+        parse_source(path, print_program(&file)).ok()?;
+        Some(file)
+    });
+    std::panic::set_hook(hook);
+    parsed.ok().flatten()
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&byte, src)) = data.split_first() else {
+        return;
+    };
+    let Ok(src) = std::str::from_utf8(src) else {
+        return;
+    };
+    let path = Path::new("input.tsx");
+    let Some(mut file) = parse_without_transform(path, src) else {
+        return;
+    };
+
+    let mut original = file.program.clone();
+    transform_file(&mut file, &config(byte)).unwrap();
+
+    let output = print_program(&file);
+    if let Err(err) = parse_source(path, output.clone()) {
+        panic!("output does not parse: {}\n{}", err, output);
+    }
+
+    original.visit_mut_with(&mut StripJsx);
+    file.program.visit_mut_with(&mut StripJsx);
+    assert!(
+        original.eq_ignore_span(&file.program),
+        "code outside of JSX changed\n{}",
+        output
+    );
+});
@@ -0,0 +1,121 @@
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        AssignExpr, AssignOp, Expr, ExprStmt, Ident, Lit, MemberExpr, MemberProp, ModuleItem,
+        PatOrExpr, Stmt, Str,
+    },
+};
+
+/**
+ * `object.prop = value;` to insert after the declaration of object
+ */
+#[derive(Clone)]
+pub struct Assignment {
+    pub object: Ident,
+    pub prop: &'static str,
+    pub value: Expr,
+}
+
+impl Assignment {
+    /**
+     * UserCard.displayName = "UserCard";
+     */
+    pub fn display_name(object: Ident) -> Self {
+        let value = Expr::Lit(Lit::Str(str_lit(&object.sym)));
+        Self {
+            object,
+            prop: "displayName",
+            value,
+        }
+    }
+
+    pub fn into_stmt(self) -> Stmt {
+        Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Assign(AssignExpr {
+                span: DUMMY_SP,
+                op: AssignOp::Assign,
+                left: PatOrExpr::Expr(member(Expr::Ident(self.object), self.prop)),
+                right: Box::new(self.value),
+            })),
+        })
+    }
+
+    /**
+     * Whether the statement already assigns the same property, e.g. by the previous run
+     */
+    pub fn is_assigned_by(&self, stmt: &Stmt) -> bool {
+        let Stmt::Expr(ExprStmt { expr, .. }) = stmt else {
+            return false;
+        };
+        let Expr::Assign(AssignExpr { left, .. }) = &**expr else {
+            return false;
+        };
+        let left = match left {
+            PatOrExpr::Expr(expr) => &**expr,
+            PatOrExpr::Pat(pat) => match pat.as_expr() {
+                Some(expr) => &**expr,
+                None => return false,
+            },
+        };
+        matches!(
+            left,
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) if &*prop.sym == self.prop
+                && matches!(&**obj, Expr::Ident(ident) if ident.sym == self.object.sym)
+        )
+    }
+}
+
+pub fn str_lit(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    }
+}
+
+/**
+ * obj.prop
+ */
+pub fn member(obj: Expr, prop: &str) -> Box<Expr> {
+    Box::new(Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(obj),
+        prop: MemberProp::Ident(Ident::new(prop.into(), DUMMY_SP)),
+    }))
+}
+
+/**
+ * Statement lists which assignments can be inserted into
+ */
+pub trait StmtLike: Sized {
+    fn as_stmt(&self) -> Option<&Stmt>;
+    fn from_stmt(stmt: Stmt) -> Self;
+}
+
+impl StmtLike for Stmt {
+    fn as_stmt(&self) -> Option<&Stmt> {
+        Some(self)
+    }
+
+    fn from_stmt(stmt: Stmt) -> Self {
+        stmt
+    }
+}
+
+impl StmtLike for ModuleItem {
+    fn as_stmt(&self) -> Option<&Stmt> {
+        match self {
+            ModuleItem::Stmt(stmt) => Some(stmt),
+            ModuleItem::ModuleDecl(_) => None,
+        }
+    }
+
+    fn from_stmt(stmt: Stmt) -> Self {
+        ModuleItem::Stmt(stmt)
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde_json::Value;
+use swc_test_plugin::{
+    codemod::{codemod_file, patch_path, unified_diff},
+    diff::{diff_ids, tree_ids},
+    runner::{format_diagnostic, parse_file, print_program, transform_file},
+    xref::tree_cross_reference,
+    CheckLevel, Config, Mode,
+};
+
+const USAGE: &str =
+    "Usage: swc-test-plugin [--config <config.json>] [--env <name>] [--write] [--codemod] [--check] [--update-lockfile] <files>...
+       swc-test-plugin [--config <config.json>] [--env <name>] --diff [--json] <old dir> <new dir>
+       swc-test-plugin [--config <config.json>] [--env <name>] --xref [--json] <app dir> <tests dir>
+
+Run the transform on .js/.jsx/.ts/.tsx files and print the result.
+
+Options:
+  -c, --config <path>  plugin config (the same JSON as in .swcrc)
+  -e, --env <name>     profile of the config to use ($NODE_ENV by default)
+  -w, --write          rewrite the files in place instead of printing
+      --codemod        only insert missing attributes into the source text,
+                       keeping formatting and comments. Print a patch unless --write
+      --check          report components missing the attribute (mode: \"check\")
+                       and exit with 1 if any error is found
+      --update-lockfile
+                       write the ids of the files to the lockfile of the config
+      --diff           report test ids added, removed and renamed between two trees
+                       (e.g. checkouts) as markdown, and exit with 1 if any id is
+                       removed or renamed
+      --xref           report selectors of the tests (getByTestId, [data-testid=...])
+                       which match no id of the app, and ids which no test uses,
+                       and exit with 1 if any selector is missing
+      --json           print the report of --diff or --xref as JSON
+  -h, --help           print this message";
+
+struct Options {
+    config: Option<PathBuf>,
+    env: Option<String>,
+    write: bool,
+    codemod: bool,
+    check: bool,
+    update_lockfile: bool,
+    diff: bool,
+    xref: bool,
+    json: bool,
+    files: Vec<PathBuf>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        config: None,
+        env: None,
+        write: false,
+        codemod: false,
+        check: false,
+        update_lockfile: false,
+        diff: false,
+        xref: false,
+        json: false,
+        files: vec![],
+    };
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => {
+                let path = args.next().ok_or("--config requires a path")?;
+                options.config = Some(path.into());
+            }
+            "-e" | "--env" => {
+                let env = args.next().ok_or("--env requires a name")?;
+                options.env = Some(env);
+            }
+            "-w" | "--write" => options.write = true,
+            "--codemod" => options.codemod = true,
+            "--check" => options.check = true,
+            "--update-lockfile" => options.update_lockfile = true,
+            "--diff" => options.diff = true,
+            "--xref" => options.xref = true,
+            "--json" => options.json = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ => options.files.push(arg.into()),
+        }
+    }
+
+    if options.files.is_empty() || ((options.diff || options.xref) && options.files.len() != 2) {
+        return Err(USAGE.to_string());
+    }
+    Ok(options)
+}
+
+fn read_config(path: &Option<PathBuf>, env: Option<&str>) -> Result<Config, String> {
+    let Some(path) = path else {
+        return Ok(Config::default());
+    };
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let plugin_config: Value =
+        serde_json::from_str(&text).map_err(|err| format!("{}: {}", path.display(), err))?;
+    Config::from_value_with_env(&plugin_config, env)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/**
+ * Print the problems of every file, and fail if any of them is an error
+ */
+fn check(config: &Config, files: &[PathBuf]) -> Result<(), String> {
+    let mut errors = 0;
+    for path in files.iter() {
+        let mut file = parse_file(path)?;
+        let Some(diagnostics) = transform_file(&mut file, config)? else {
+            continue;
+        };
+        for diagnostic in diagnostics.iter() {
+            eprintln!("{}", format_diagnostic(&file, diagnostic));
+            if diagnostic.level == CheckLevel::Error {
+                errors += 1;
+            }
+        }
+    }
+
+    if errors > 0 {
+        return Err(format!("{} error(s) found", errors));
+    }
+    Ok(())
+}
+
+/**
+ * Print the changes of the ids between the two trees, and fail if any id is removed or renamed
+ */
+fn diff(config: &Config, old: &Path, new: &Path, json: bool) -> Result<(), String> {
+    let (old, new) = (tree_ids(old, config)?, tree_ids(new, config)?);
+    for error in old.errors.iter().chain(new.errors.iter()) {
+        eprintln!("skipped {}", error);
+    }
+    let diff = diff_ids(&old, &new);
+    if json {
+        println!("{:#}", diff.to_json());
+    } else {
+        print!("{}", diff.to_markdown());
+    }
+
+    if diff.is_breaking() {
+        return Err(format!(
+            "{} test id(s) removed or renamed",
+            diff.removed.len() + diff.renamed.len()
+        ));
+    }
+    Ok(())
+}
+
+/**
+ * Print the selectors of the tests without an id and the ids without a test, and fail if any selector is missing
+ */
+fn xref(config: &Config, app: &Path, tests: &Path, json: bool) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|err| err.to_string())?;
+    let (xref, errors) = tree_cross_reference(&cwd, app, tests, config)?;
+    for error in errors.iter() {
+        eprintln!("skipped {}", error);
+    }
+    if json {
+        println!("{:#}", xref.to_json());
+    } else {
+        print!("{}", xref.to_markdown());
+    }
+
+    if !xref.missing.is_empty() {
+        return Err(format!(
+            "{} selector(s) match no test id",
+            xref.missing.len()
+        ));
+    }
+    Ok(())
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let env = options
+        .env
+        .clone()
+        .or_else(|| std::env::var("NODE_ENV").ok());
+    let mut config = read_config(&options.config, env.as_deref())?;
+    if options.check {
+        config.mode = Mode::Check;
+    }
+    if options.update_lockfile {
+        if config.lockfile.is_empty() {
+            return Err("--update-lockfile requires lockfile in the config".to_string());
+        }
+        config.update_lockfile = true;
+    }
+    if options.diff {
+        return diff(&config, &options.files[0], &options.files[1], options.json);
+    }
+    if options.xref {
+        return xref(&config, &options.files[0], &options.files[1], options.json);
+    }
+    if config.mode == Mode::Check {
+        return check(&config, &options.files);
+    }
+    let print_header = !options.write && !options.codemod && options.files.len() > 1;
+
+    for path in options.files.iter() {
+        let mut file = parse_file(path)?;
+        let output = if options.codemod {
+            codemod_file(&mut file, &config)
+                .map_err(|err| format!("{}: {}", path.display(), err))?
+        } else {
+            transform_file(&mut file, &config)?.map(|_| print_program(&file))
+        };
+        let Some(output) = output else {
+            eprintln!("{}: ignored by ignoreFiles", path.display());
+            continue;
+        };
+
+        if options.codemod && !options.write {
+            let cwd = std::env::current_dir().map_err(|err| err.to_string())?;
+            let path = patch_path(path, &cwd)?;
+            print!("{}", unified_diff(&path, &file.fm.src, &output));
+            continue;
+        }
+
+        if options.write {
+            std::fs::write(path, output).map_err(|err| format!("{}: {}", path.display(), err))?;
+        } else {
+            if print_header {
+                println!("// {}", path.display());
+            }
+            print!("{}", output);
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options() {
+        let options = parse(&[
+            "-c", "swc.json", "--env", "test", "-w", "--check", "a.tsx", "b.tsx",
+        ])
+        .ok()
+        .unwrap();
+        assert_eq!(options.config, Some(PathBuf::from("swc.json")));
+        assert_eq!(options.env.as_deref(), Some("test"));
+        assert!(options.write && options.check);
+        assert!(!options.codemod && !options.diff && !options.json);
+        assert_eq!(
+            options.files,
+            vec![PathBuf::from("a.tsx"), PathBuf::from("b.tsx")]
+        );
+
+        let options = parse(&["--diff", "--json", "old", "new"]).ok().unwrap();
+        assert!(options.diff && options.json);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let err = |args: &[&str]| parse(args).err().unwrap();
+        assert_eq!(err(&["--config"]), "--config requires a path");
+        assert_eq!(err(&["-e"]), "--env requires a name");
+        assert_eq!(err(&["--fix", "a.tsx"]), "unknown option: --fix");
+        // files, and exactly two trees for --diff and --xref
+        assert_eq!(err(&["--write"]), USAGE);
+        assert_eq!(err(&["--diff", "old"]), USAGE);
+        assert_eq!(err(&["--xref", "src", "e2e", "tests"]), USAGE);
+        assert_eq!(err(&["--help", "a.tsx"]), USAGE);
+    }
+}
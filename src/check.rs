@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use swc_core::common::{errors::Handler, Span};
+
+/**
+ * Severity of the problems found in `mode: "check"`
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckLevel {
+    #[default]
+    Error,
+    Warning,
+}
+
+/**
+ * A component root which is missing the attribute
+ * or whose attribute does not match `idTemplate`
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckDiagnostic {
+    pub span: Span,
+    pub level: CheckLevel,
+    pub message: String,
+}
+
+impl CheckDiagnostic {
+    pub fn emit(&self, handler: &Handler) {
+        match self.level {
+            CheckLevel::Error => handler.span_err(self.span, &self.message),
+            CheckLevel::Warning => handler.span_warn(self.span, &self.message),
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use std::path::Path;
+
+use swc_core::{
+    common::{BytePos, Spanned},
+    ecma::{
+        ast::{
+            CallExpr, Expr, JSXAttrName, JSXAttrOrSpread, JSXAttrValue, JSXOpeningElement,
+            KeyValueProp, Lit, ObjectLit, Prop, PropName, PropOrSpread, Str,
+        },
+        visit::{Visit, VisitWith},
+    },
+};
+
+use crate::{
+    path::relative_path,
+    runner::{transform_file, ParsedFile},
+    Config, Hierarchy, Styled, ValueMode,
+};
+
+/**
+ * Replacement of `len` bytes at byte `offset` of the original source with `text`,
+ * an insertion if `len` is 0
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub offset: usize,
+    pub len: usize,
+    pub text: String,
+}
+
+/**
+ * Source text of a string literal
+ */
+fn str_text(s: &Str) -> String {
+    match &s.raw {
+        Some(raw) => raw.to_string(),
+        None => format!("\"{}\"", s.value),
+    }
+}
+
+/**
+ * `"key": "value"` of a prop added by TransformVisitor
+ */
+fn prop_text(prop: &PropOrSpread) -> Option<String> {
+    let PropOrSpread::Prop(prop) = prop else {
+        return None;
+    };
+    let Prop::KeyValue(KeyValueProp { key, value }) = &**prop else {
+        return None;
+    };
+    let key = match key {
+        PropName::Str(s) => str_text(s),
+        PropName::Ident(ident) => ident.sym.to_string(),
+        _ => return None,
+    };
+    match &**value {
+        Expr::Lit(Lit::Str(s)) => Some(format!("{}: {}", key, str_text(s))),
+        _ => None,
+    }
+}
+
+/**
+ * Find attributes and props which were added by TransformVisitor.
+ * They are the only attributes, props and props objects without a span.
+ */
+struct InsertionCollector<'a> {
+    src: &'a str,
+    start_pos: BytePos,
+    edits: Vec<TextEdit>,
+}
+
+impl InsertionCollector<'_> {
+    fn offset(&self, pos: BytePos) -> usize {
+        (pos - self.start_pos).0 as usize
+    }
+
+    fn insert(&mut self, pos: BytePos, text: String) {
+        self.edits.push(TextEdit {
+            offset: self.offset(pos),
+            len: 0,
+            text,
+        });
+    }
+
+    /**
+     * Props added to an object literal of the source
+     * createElement("div", { id }) -> createElement("div", { id, "data-testid": "a" })
+     * createElement("div", { ...p }) -> createElement("div", { "data-testid": "a", ...p })
+     */
+    fn collect_props(&mut self, object: &ObjectLit) {
+        for (i, prop) in object.props.iter().enumerate() {
+            if !prop.span().is_dummy() {
+                continue;
+            }
+            let Some(text) = prop_text(prop) else {
+                continue;
+            };
+            let written_before = object.props[..i]
+                .iter()
+                .map(Spanned::span)
+                .filter(|span| !span.is_dummy())
+                .map(|span| span.hi)
+                .max();
+            match written_before {
+                Some(hi) => self.insert(hi, format!(", {}", text)),
+                // right after `{`
+                None if i + 1 < object.props.len() => {
+                    self.insert(object.span.lo + BytePos(1), format!(" {},", text))
+                }
+                None => self.insert(object.span.lo + BytePos(1), format!(" {} ", text)),
+            }
+        }
+    }
+
+    /**
+     * Props object added as the argument `index`
+     * createElement("div")           -> createElement("div", { "data-testid": "a" })
+     * createElement("div", null)     -> createElement("div", { "data-testid": "a" })
+     * jsx("div", props)              -> jsx("div", { "data-testid": "a", ...props })
+     */
+    fn collect_props_arg(&mut self, call: &CallExpr, index: usize, object: &ObjectLit) {
+        let mut texts = vec![];
+        for prop in object.props.iter() {
+            match prop {
+                PropOrSpread::Spread(spread) if !spread.expr.span().is_dummy() => {
+                    let span = spread.expr.span();
+                    self.insert(span.lo, format!("{{ {}, ...", texts.join(", ")));
+                    self.insert(span.hi, " }".to_string());
+                    return;
+                }
+                prop => match prop_text(prop) {
+                    Some(text) => texts.push(text),
+                    None => return,
+                },
+            }
+        }
+        let text = format!("{{ {} }}", texts.join(", "));
+
+        // between the type and the next argument (or `)`)
+        let Some(previous) = index.checked_sub(1).and_then(|i| call.args.get(i)) else {
+            return;
+        };
+        let start = previous.expr.span().hi;
+        let end = match call.args.get(index + 1) {
+            Some(next) => next.expr.span().lo,
+            None => call.span.hi - BytePos(1),
+        };
+        let between = &self.src[self.offset(start)..self.offset(end).max(self.offset(start))];
+        let rest = between.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        if rest.starts_with("null") {
+            self.edits.push(TextEdit {
+                offset: self.offset(start) + between.len() - rest.len(),
+                len: "null".len(),
+                text,
+            });
+        } else {
+            self.insert(start, format!(", {}", text));
+        }
+    }
+}
+
+impl Visit for InsertionCollector<'_> {
+    fn visit_call_expr(&mut self, n: &CallExpr) {
+        for (i, arg) in n.args.iter().enumerate() {
+            let Expr::Object(object) = &*arg.expr else {
+                continue;
+            };
+            if arg.spread.is_some() {
+                continue;
+            }
+            if object.span.is_dummy() {
+                self.collect_props_arg(n, i, object);
+            } else {
+                self.collect_props(object);
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_jsx_opening_element(&mut self, n: &JSXOpeningElement) {
+        for (i, attr_or_spread) in n.attrs.iter().enumerate() {
+            let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread else {
+                continue;
+            };
+            if !attr.span.is_dummy() {
+                continue;
+            }
+            let JSXAttrName::Ident(name) = &attr.name else {
+                continue;
+            };
+            let value = match &attr.value {
+                Some(JSXAttrValue::Lit(Lit::Str(s))) => str_text(s),
+                _ => continue,
+            };
+
+            // insert right after the last attribute written in the source before it
+            // <div className="a"> -> <div className="a" data-testid="b">
+            // <div {...props}> -> <div data-testid="b" {...props}> (hierarchy "file")
+            let mut hi = n.name.span().hi;
+            if let Some(type_args) = &n.type_args {
+                hi = hi.max(type_args.span.hi);
+            }
+            for written in n.attrs[..i].iter() {
+                let span = written.span();
+                if !span.is_dummy() {
+                    hi = hi.max(span.hi);
+                }
+            }
+
+            self.insert(hi, format!(" {}={}", name.sym, value));
+        }
+
+        n.visit_children_with(self);
+    }
+}
+
+/**
+ * Insert the missing attributes and props into the source text of the file.
+ * Formatting and comments are kept as is because nothing is re-generated.
+ * Return None if the file is ignored by `ignoreFiles`.
+ */
+pub fn codemod_file(file: &mut ParsedFile, config: &Config) -> Result<Option<String>, String> {
+    // statements and styled calls are not insertions into the line they belong to
+    if config.display_name {
+        return Err(
+            "--codemod does not support displayName, turn it off in the config".to_string(),
+        );
+    }
+    if config.styled != Styled::Off {
+        return Err("--codemod does not support styled, turn it off in the config".to_string());
+    }
+    // testId() of valueMode "runtime" would need its import as well,
+    // the scopes of hierarchy "runtime" are not insertions,
+    // and source locations are for builds, not for the committed source
+    let config = Config {
+        value_mode: ValueMode::Literal,
+        hierarchy: match config.hierarchy {
+            Hierarchy::Runtime => Hierarchy::Off,
+            hierarchy => hierarchy,
+        },
+        source_location: false,
+        ..config.clone()
+    };
+    if transform_file(file, &config)?.is_none() {
+        return Ok(None);
+    }
+
+    let mut collector = InsertionCollector {
+        src: &file.fm.src,
+        start_pos: file.fm.start_pos,
+        edits: vec![],
+    };
+    file.program.visit_with(&mut collector);
+
+    Ok(Some(apply_edits(&file.fm.src, collector.edits)))
+}
+
+pub fn apply_edits(src: &str, mut edits: Vec<TextEdit>) -> String {
+    edits.sort_by_key(|edit| edit.offset);
+
+    let mut output = String::with_capacity(src.len());
+    let mut last = 0;
+    for edit in edits.iter() {
+        output.push_str(&src[last..edit.offset]);
+        output.push_str(&edit.text);
+        last = edit.offset + edit.len;
+    }
+    output.push_str(&src[last..]);
+    output
+}
+
+/**
+ * Path of the file in the patch, relative to where `git apply` applies it:
+ * the top of the git repository around cwd, or else cwd. rootDir doesn't matter here,
+ * and absolute paths are rejected by `git apply`.
+ * ./src/App.tsx (cwd /home/me/app)                           -> "src/App.tsx"
+ * ./src/App.tsx (cwd /home/me/repo/web, repo /home/me/repo) -> "web/src/App.tsx"
+ */
+pub fn patch_path(path: &Path, cwd: &Path) -> Result<String, String> {
+    let root = (cwd.ancestors())
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(cwd);
+    let relative = relative_path(
+        &cwd.join(path).to_string_lossy(),
+        Some(&root.to_string_lossy()),
+    );
+    if relative.starts_with('/') || relative.get(1..2) == Some(":") {
+        return Err(format!(
+            "{}: --codemod only patches files inside the repository or cwd",
+            path.display()
+        ));
+    }
+    Ok(relative)
+}
+
+/**
+ * Unified diff of the codemod, which can be applied with `git apply`.
+ * Edits never add or remove lines,
+ * so the n-th line of `old` always corresponds to the n-th line of `new`.
+ */
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+    debug_assert_eq!(old_lines.len(), new_lines.len());
+
+    let changed = (0..old_lines.len())
+        .filter(|&i| old_lines[i] != new_lines[i])
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // merge changes whose context overlaps into one hunk
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &i in changed.iter() {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(old_lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    for (start, end) in hunks {
+        let len = end - start;
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            len,
+            start + 1,
+            len
+        ));
+
+        let mut i = start;
+        while i < end {
+            if old_lines[i] == new_lines[i] {
+                push_diff_line(&mut diff, ' ', old_lines[i]);
+                i += 1;
+                continue;
+            }
+            // removed lines first, then added lines
+            let run_end = (i..end)
+                .find(|&j| old_lines[j] == new_lines[j])
+                .unwrap_or(end);
+            for line in old_lines[i..run_end].iter() {
+                push_diff_line(&mut diff, '-', line);
+            }
+            for line in new_lines[i..run_end].iter() {
+                push_diff_line(&mut diff, '+', line);
+            }
+            i = run_end;
+        }
+    }
+    diff
+}
+
+fn push_diff_line(diff: &mut String, marker: char, line: &str) {
+    diff.push(marker);
+    diff.push_str(line);
+    if !line.ends_with('\n') {
+        diff.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::runner::parse_source;
+
+    fn codemod(src: &str) -> String {
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            ..Default::default()
+        };
+        let mut file = parse_source(Path::new("input.tsx"), src.to_string()).unwrap();
+        codemod_file(&mut file, &config).unwrap().unwrap()
+    }
+
+    #[test]
+    fn keeps_formatting_and_comments() {
+        let src = r#"// header
+export const UserCard = () => {
+  // keep me
+  return (
+    <div
+      className="card" // trailing
+    >
+      <img src="a.png" lazy-load="false" />
+    </div>
+  )
+}
+"#;
+        let expected = r#"// header
+export const UserCard = () => {
+  // keep me
+  return (
+    <div
+      className="card" data-testid="user-card" // trailing
+    >
+      <img src="a.png" lazy-load="false" />
+    </div>
+  )
+}
+"#;
+        assert_eq!(codemod(src), expected);
+    }
+
+    #[test]
+    fn inserts_after_element_name() {
+        let src = "function Div() {\n  return <div/>\n}\n\nfunction Text() {\n  return <h3 data-testid=\"text\">text</h3>\n}\n";
+        let expected = "function Div() {\n  return <div data-testid=\"div\"/>\n}\n\nfunction Text() {\n  return <h3 data-testid=\"text\">text</h3>\n}\n";
+        assert_eq!(codemod(src), expected);
+    }
+
+    #[test]
+    fn inserts_before_spread_props() {
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            hierarchy: Hierarchy::File,
+            ..Default::default()
+        };
+        let src = "function Card(props) {\n  return <div {...props}><Avatar /></div>\n}\n";
+        let expected = "function Card(props) {\n  return <div data-testid=\"card\" {...props}><Avatar data-testid=\"card__avatar\" /></div>\n}\n";
+        let mut file = parse_source(Path::new("input.tsx"), src.to_string()).unwrap();
+        assert_eq!(codemod_file(&mut file, &config).unwrap().unwrap(), expected);
+    }
+
+    #[test]
+    fn inserts_props_of_element_calls() {
+        let src = r#"function Div() {
+  return React.createElement("div", { className: "a" })
+}
+function Empty() {
+  return React.createElement("div", {})
+}
+function Null() {
+  return React.createElement("div", null, "text")
+}
+function NoProps() {
+  return createElement("div")
+}
+function Spread(props) {
+  return jsx("div", props)
+}
+"#;
+        let expected = r#"function Div() {
+  return React.createElement("div", { className: "a", "data-testid": "div" })
+}
+function Empty() {
+  return React.createElement("div", { "data-testid": "empty" })
+}
+function Null() {
+  return React.createElement("div", { "data-testid": "null" }, "text")
+}
+function NoProps() {
+  return createElement("div", { "data-testid": "no-props" })
+}
+function Spread(props) {
+  return jsx("div", { "data-testid": "spread", ...props })
+}
+"#;
+        assert_eq!(codemod(src), expected);
+    }
+
+    #[test]
+    fn leaves_out_source_location() {
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            source_location: true,
+            ..Default::default()
+        };
+        let src = "function Div() {\n  return <div/>\n}\n";
+        let expected = "function Div() {\n  return <div data-testid=\"div\"/>\n}\n";
+        let mut file = parse_source(Path::new("input.tsx"), src.to_string()).unwrap();
+        assert_eq!(codemod_file(&mut file, &config).unwrap().unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_statements_and_styled() {
+        let src = "const Wrapper = styled.div``\n";
+        for config in [
+            Config {
+                display_name: true,
+                ..Default::default()
+            },
+            Config {
+                styled: Styled::Attrs,
+                ..Default::default()
+            },
+        ] {
+            let mut file = parse_source(Path::new("input.tsx"), src.to_string()).unwrap();
+            assert!(codemod_file(&mut file, &config).is_err());
+        }
+    }
+
+    #[test]
+    fn patch_paths() {
+        let cwd = Path::new("/home/me/app");
+        assert_eq!(
+            patch_path(Path::new("/home/me/app/src/App.tsx"), cwd).unwrap(),
+            "src/App.tsx"
+        );
+        assert_eq!(
+            patch_path(Path::new("./src/App.tsx"), cwd).unwrap(),
+            "src/App.tsx"
+        );
+        assert!(patch_path(Path::new("/tmp/App.tsx"), cwd).is_err());
+
+        // rootDir of a package in a repository, with the codemod run from the package
+        let repo =
+            std::env::temp_dir().join(format!("swc-test-plugin-patch-{}", std::process::id()));
+        let cwd = repo.join("packages/web");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&cwd).unwrap();
+        assert_eq!(
+            patch_path(Path::new("src/App.tsx"), &cwd).unwrap(),
+            "packages/web/src/App.tsx"
+        );
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn diff_of_inserted_lines() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni\n";
+        let expected =
+            "--- a/x.tsx\n+++ b/x.tsx\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n";
+        assert_eq!(unified_diff("x.tsx", old, new), expected);
+        assert_eq!(unified_diff("x.tsx", old, old), "");
+    }
+}
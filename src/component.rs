@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use swc_core::ecma::{
+    ast::{ArrowExpr, CallExpr, Callee, Expr, Function, Ident, MemberProp},
+    visit::{Visit, VisitWith},
+};
+
+/**
+ * Which functions are treated as components
+ *
+ * pascalCase:   the name starts with an uppercase letter (UserCard, not renderRow)
+ * returnsJsx:   the function returns a JSX element
+ * requireHooks: the function calls a hook (useState(), React.useMemo(), ...)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ComponentDetection {
+    pub pascal_case: bool,
+    pub returns_jsx: bool,
+    pub require_hooks: bool,
+}
+
+impl Default for ComponentDetection {
+    fn default() -> Self {
+        Self {
+            pascal_case: true,
+            returns_jsx: true,
+            require_hooks: false,
+        }
+    }
+}
+
+/**
+ * What to do with the JSX of functions which are not components (e.g. renderRow())
+ *
+ * skip:                leave it alone
+ * enclosing-component: the root gets the id of the component the function is declared in
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HelperFunctions {
+    #[default]
+    Skip,
+    EnclosingComponent,
+}
+
+/**
+ * Ids of JSX returned from render props (`render={() => <Page />}`, `{(form) => <form />}`)
+ *
+ * off:       left alone, they are children of the parent's JSX
+ * index:     "{component}__render-0", "{component}__render-1", ... in the order of the code
+ * attr-name: "{component}__render" by the attribute name, "{component}__children" for children
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RenderProps {
+    #[default]
+    Off,
+    Index,
+    AttrName,
+}
+
+/**
+ * Function whose JSX is being visited
+ */
+#[derive(Debug, Clone)]
+pub struct ComponentScope {
+    pub name: Ident,
+    /// "render-0" or the attribute name for render props
+    pub render: Option<String>,
+}
+
+impl ComponentScope {
+    pub fn new(name: Ident) -> Self {
+        Self { name, render: None }
+    }
+}
+
+pub fn is_pascal_case(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
+/**
+ * memo(), forwardRef(), React.memo() or React.forwardRef(), which wrap a component
+ */
+pub fn is_component_wrapper(callee: &Callee) -> bool {
+    let Callee::Expr(callee) = callee else {
+        return false;
+    };
+    let name = match &**callee {
+        Expr::Ident(ident) => &ident.sym,
+        Expr::Member(member) => match (&*member.obj, &member.prop) {
+            (Expr::Ident(obj), MemberProp::Ident(prop)) if &*obj.sym == "React" => &prop.sym,
+            _ => return false,
+        },
+        _ => return false,
+    };
+    &**name == "memo" || &**name == "forwardRef"
+}
+
+/**
+ * useState, useMemo, ... (but not user or use)
+ */
+fn is_hook_name(name: &str) -> bool {
+    name.strip_prefix("use")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/**
+ * Find hook calls in the body of a function.
+ * Calls in nested functions (e.g. callbacks of useEffect()) belong to those functions.
+ */
+#[derive(Default)]
+pub struct HookFinder {
+    pub found: bool,
+}
+
+impl Visit for HookFinder {
+    fn visit_call_expr(&mut self, n: &CallExpr) {
+        if let Callee::Expr(callee) = &n.callee {
+            match &**callee {
+                // useState()
+                Expr::Ident(ident) if is_hook_name(&ident.sym) => self.found = true,
+                // React.useState()
+                Expr::Member(member) => {
+                    if let MemberProp::Ident(ident) = &member.prop {
+                        if is_hook_name(&ident.sym) {
+                            self.found = true;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/**
+ * Whether the function body calls a hook
+ */
+pub fn calls_hook<N: VisitWith<HookFinder>>(body: &N) -> bool {
+    let mut finder = HookFinder::default();
+    body.visit_with(&mut finder);
+    finder.found
+}
@@ -0,0 +1,182 @@
+use serde::Deserialize;
+use serde_json::Value;
+use swc_core::common::FileName;
+
+use crate::{
+    path::{project_root, relative_path},
+    CheckLevel, ComponentDetection, HelperFunctions, Hierarchy, Preset, RenderProps, RouteIds,
+    Styled, ValueMode,
+};
+
+/**
+ * What the plugin does with component roots
+ *
+ * transform: add the attribute if it is missing
+ * check:     report roots missing the attribute without modifying code
+ * strip:     remove the attribute (and the source location) from every element
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    #[default]
+    Transform,
+    Check,
+    Strip,
+}
+
+/**
+ * The plugin config (the second element of `jsc.experimental.plugins`) with camelCase keys.
+ * ignoreFiles and ignoreComponents are required,
+ * and unknown keys are rejected so that typos don't go unnoticed.
+ */
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub attr_name: String,
+    pub ignore_files: Vec<String>,
+    pub ignore_components: Vec<String>,
+    #[serde(default)]
+    pub preset: Preset,
+    /// "{component}" is replaced with the component name in the case style of the preset
+    #[serde(default)]
+    pub id_template: String,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub check_level: CheckLevel,
+    #[serde(default)]
+    pub component_detection: ComponentDetection,
+    #[serde(default)]
+    pub helper_functions: HelperFunctions,
+    #[serde(default)]
+    pub render_props: RenderProps,
+    #[serde(default)]
+    pub styled: Styled,
+    /// append `Component.displayName = "Component"` (development builds only)
+    #[serde(default)]
+    pub display_name: bool,
+    /// roots whose first host child gets the attribute instead ("*.Provider" for any Provider), besides Fragment and the like
+    #[serde(default)]
+    pub transparent_elements: Vec<String>,
+    /// functions which create an element besides createElement and the jsx runtime, e.g. h or Vue.h
+    #[serde(default)]
+    pub element_factories: Vec<String>,
+    /// ids of Next.js pages by their routes
+    #[serde(default)]
+    pub route_ids: Option<RouteIds>,
+    /// project root which file paths are relative to, the working directory of swc by default
+    #[serde(default)]
+    pub root_dir: String,
+    #[serde(default)]
+    pub value_mode: ValueMode,
+    /// module which `valueMode: "runtime"` imports testId from
+    #[serde(default)]
+    pub runtime_module: String,
+    #[serde(default)]
+    pub hierarchy: Hierarchy,
+    /// JSON of the ids of components by file, relative to rootDir
+    #[serde(default)]
+    pub lockfile: String,
+    /// write the ids of the transformed files to the lockfile
+    #[serde(default)]
+    pub update_lockfile: bool,
+    /// add `data-source-location="path:line:column"` to component roots
+    #[serde(default)]
+    pub source_location: bool,
+}
+
+impl Config {
+    /**
+     * Read the plugin config
+     */
+    pub fn from_value(plugin_config: &Value) -> Result<Self, String> {
+        let config = serde_json::from_value::<Config>(plugin_config.clone())
+            .map_err(|err| format!("invalid plugin config: {}", err))?;
+
+        if (config.value_mode == ValueMode::Runtime || config.hierarchy == Hierarchy::Runtime)
+            && config.runtime_module.is_empty()
+        {
+            return Err("invalid plugin config: runtimeModule is expected \
+                 with valueMode runtime or hierarchy runtime"
+                .to_string());
+        }
+        Ok(config)
+    }
+
+    /**
+     * Read the plugin config for the env (`envName` of swc, "development" by default),
+     * with `profiles[env]` merged over the other keys
+     *
+     * {
+     *   "attrName": "data-testid",
+     *   "profiles": {
+     *     "development": { "sourceLocation": true },
+     *     "production": { "mode": "strip" }
+     *   }
+     * }
+     */
+    pub fn from_value_with_env(plugin_config: &Value, env: Option<&str>) -> Result<Self, String> {
+        let mut plugin_config = plugin_config.clone();
+        let profiles = match &mut plugin_config {
+            Value::Object(keys) => keys.remove("profiles"),
+            _ => None,
+        };
+        let profile = match (profiles, env) {
+            (Some(Value::Object(mut profiles)), Some(env)) => profiles.remove(env),
+            (Some(Value::Object(_)), None) | (None, _) => None,
+            (Some(_), _) => {
+                return Err(
+                    "invalid plugin config: profiles should be an object keyed by env name"
+                        .to_string(),
+                )
+            }
+        };
+        match profile {
+            Some(Value::Object(profile)) => {
+                for (key, value) in profile {
+                    plugin_config[key] = value;
+                }
+            }
+            Some(_) => {
+                return Err(format!(
+                    "invalid plugin config: profiles.{} should be an object",
+                    env.unwrap_or_default()
+                ))
+            }
+            None => (),
+        }
+
+        let mut config = Self::from_value(&plugin_config)?;
+        // displayName is for React DevTools, so only for development builds
+        if env.is_some_and(|env| env != "development") {
+            config.display_name = false;
+        }
+        Ok(config)
+    }
+
+    /**
+     * Path of the file relative to rootDir (or cwd, the working directory of swc) with `/` separators,
+     * which ignoreFiles, routeIds and sourceLocation see
+     * /home/me/app/src/App.tsx -> src/App.tsx
+     */
+    pub fn relative_filename(&self, filename: &FileName, cwd: Option<&str>) -> FileName {
+        match filename {
+            FileName::Real(path) => {
+                let root = project_root(&self.root_dir, cwd);
+                FileName::Real(relative_path(&path.to_string_lossy(), root.as_deref()).into())
+            }
+            filename => filename.clone(),
+        }
+    }
+
+    /**
+     * Whether the file path contains one of ignoreFiles
+     */
+    pub fn is_ignored_file(&self, filename: &FileName) -> bool {
+        let filename = filename.to_string();
+        self.ignore_files
+            .iter()
+            .any(|ignore_file| filename.contains(ignore_file.trim_matches('\"')))
+    }
+}
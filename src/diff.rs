@@ -0,0 +1,639 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+use swc_core::{
+    common::{Span, Spanned},
+    ecma::{
+        ast::{
+            CallExpr, Expr, JSXAttrOrSpread, JSXAttrValue, JSXExpr, JSXExprContainer,
+            JSXOpeningElement, KeyValueProp, Lit, Program, PropName, Stmt, Str,
+        },
+        visit::{Visit, VisitWith},
+    },
+};
+
+use crate::{
+    jsx_attr_name, jsx_element_name,
+    runner::{parse_file, transform_file, ParsedFile},
+    runtime::{helper_value, RuntimeNames},
+    Config,
+};
+
+/**
+ * A test id in the output of the transform
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestId {
+    pub id: String,
+    /// relative to the root of the tree, with `/` separators
+    pub file: String,
+    pub line: usize,
+}
+
+impl TestId {
+    pub(crate) fn to_json(&self) -> Value {
+        json!({ "id": self.id, "file": self.file, "line": self.line })
+    }
+}
+
+/**
+ * "user-card" of data-testid="user-card", data-testid={_testId("user-card")} and the like
+ */
+fn id_value<'a>(expr: &'a Expr, names: &RuntimeNames) -> Option<&'a Str> {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => Some(s),
+        expr => helper_value(expr, &names.helper),
+    }
+}
+
+/**
+ * Find the values of the attribute: JSX attributes, props of element calls and styled primitives,
+ * and the ids of TestIdScope
+ */
+struct IdCollector<'a> {
+    attr_name: &'a str,
+    names: RuntimeNames,
+    /// the innermost element or statement written in the source, as attributes added by the transform have no span
+    span: Span,
+    ids: Vec<(String, Span)>,
+}
+
+impl IdCollector<'_> {
+    fn set_span(&mut self, span: Span) {
+        if !span.is_dummy() {
+            self.span = span;
+        }
+    }
+}
+
+impl Visit for IdCollector<'_> {
+    fn visit_stmt(&mut self, n: &Stmt) {
+        self.set_span(n.span());
+        n.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, n: &CallExpr) {
+        self.set_span(n.span);
+        n.visit_children_with(self);
+    }
+
+    fn visit_jsx_opening_element(&mut self, n: &JSXOpeningElement) {
+        self.set_span(n.span);
+        let is_scope = jsx_element_name(&n.name) == *self.names.scope;
+        for attr_or_spread in n.attrs.iter() {
+            let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread else {
+                continue;
+            };
+            let name = jsx_attr_name(&attr.name);
+            if name != self.attr_name && !(is_scope && name == "id") {
+                continue;
+            }
+            let value = match &attr.value {
+                Some(JSXAttrValue::Lit(Lit::Str(s))) => Some(s),
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) => id_value(expr, &self.names),
+                _ => None,
+            };
+            if let Some(s) = value {
+                self.ids.push((s.value.to_string(), self.span));
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_key_value_prop(&mut self, n: &KeyValueProp) {
+        let key = match &n.key {
+            PropName::Ident(ident) => Some(&*ident.sym),
+            PropName::Str(s) => Some(&*s.value),
+            _ => None,
+        };
+        if key == Some(self.attr_name) {
+            if let Some(s) = id_value(&n.value, &self.names) {
+                self.ids.push((s.value.to_string(), self.span));
+            }
+        }
+        n.visit_children_with(self);
+    }
+}
+
+/**
+ * Ids of the transformed file in the order of the code
+ */
+pub fn collect_ids(file: &ParsedFile, attr_name: &str, relative_path: &str) -> Vec<TestId> {
+    let mut collector = IdCollector {
+        attr_name,
+        names: match &file.program {
+            Program::Module(module) => RuntimeNames::of_module(module, None),
+            Program::Script(script) => RuntimeNames::of_script(script),
+        },
+        span: Default::default(),
+        ids: vec![],
+    };
+    file.program.visit_with(&mut collector);
+    collector
+        .ids
+        .into_iter()
+        .map(|(id, span)| TestId {
+            id,
+            file: relative_path.to_string(),
+            line: file.cm.lookup_char_pos(span.lo).line,
+        })
+        .collect()
+}
+
+/**
+ * .js/.jsx/.ts/.tsx files under the directory, except node_modules and hidden directories
+ */
+pub(crate) fn source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("{}: {}", dir.display(), err))?;
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() {
+            if name != "node_modules" && !name.starts_with('.') {
+                source_files(&path, files)?;
+            }
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("js" | "jsx" | "ts" | "tsx" | "mjs" | "mts" | "cjs" | "cts")
+        ) && !name.ends_with(".d.ts")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Ids of the files of a tree
+ */
+#[derive(Debug, Default)]
+pub struct TreeIds {
+    pub ids: Vec<TestId>,
+    /// number of lines of every file, including the ones without ids
+    pub lines: BTreeMap<String, usize>,
+    /// files which failed to parse and were skipped
+    pub errors: Vec<String>,
+}
+
+/**
+ * Run the transform over every file of the tree (e.g. a checkout) and collect the ids.
+ * Paths of the tree are relative to its root, which rootDir of the config is resolved against.
+ */
+pub fn tree_ids(root: &Path, config: &Config) -> Result<TreeIds, String> {
+    dir_ids(root, Path::new(""), config)
+}
+
+/**
+ * Ids of the files under a directory of the tree (relative to its root), with paths still relative to the root
+ */
+pub(crate) fn dir_ids(root: &Path, dir: &Path, config: &Config) -> Result<TreeIds, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("{}: {}", root.display(), err))?;
+    let config = Config {
+        root_dir: root.join(&config.root_dir).to_string_lossy().to_string(),
+        update_lockfile: false,
+        ..config.clone()
+    };
+    let attr_name = if config.attr_name.is_empty() {
+        config.preset.attr_name()
+    } else {
+        &config.attr_name
+    };
+
+    let mut files = vec![];
+    source_files(&root.join(dir), &mut files)?;
+
+    let mut tree = TreeIds::default();
+    for path in files.iter() {
+        let mut file = match parse_file(path) {
+            Ok(file) => file,
+            Err(err) => {
+                tree.errors.push(err);
+                continue;
+            }
+        };
+        if transform_file(&mut file, &config)?.is_none() {
+            continue;
+        }
+        let relative_path = path
+            .strip_prefix(&root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        tree.ids
+            .extend(collect_ids(&file, attr_name, &relative_path));
+        tree.lines
+            .insert(relative_path, file.fm.src.lines().count());
+    }
+    Ok(tree)
+}
+
+/**
+ * Changes of the ids between two trees.
+ * Ids which moved to another place keep working, so only ids which are gone or new count.
+ */
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IdDiff {
+    pub added: Vec<TestId>,
+    pub removed: Vec<TestId>,
+    /// (old, new) pairs of a removed and an added id in the same file, or in a renamed file
+    pub renamed: Vec<(TestId, TestId)>,
+}
+
+/**
+ * Lines which a renamed id may move by, e.g. with an import added above it,
+ * at least RENAME_LINES and else a RENAME_SHARE of the file
+ */
+const RENAME_LINES: usize = 5;
+const RENAME_SHARE: usize = 10;
+
+fn rename_window(old: &TreeIds, new: &TreeIds, old_file: &str, new_file: &str) -> usize {
+    let lines = old.lines.get(old_file).copied().unwrap_or_default();
+    let lines = lines.max(new.lines.get(new_file).copied().unwrap_or_default());
+    RENAME_LINES.max(lines / RENAME_SHARE)
+}
+
+fn directory(file: &str) -> &str {
+    file.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/**
+ * (old, new) pairs of files which are gone and new, e.g. UserCard.tsx renamed to ProfileCard.tsx
+ * together with its component: the files share an id, or are the only gone and new file of a directory
+ */
+fn renamed_files<'a>(old: &'a TreeIds, new: &'a TreeIds) -> Vec<(&'a str, &'a str)> {
+    let gone: Vec<&str> = (old.lines.keys())
+        .filter(|file| !new.lines.contains_key(*file))
+        .map(String::as_str)
+        .collect();
+    let created: Vec<&str> = (new.lines.keys())
+        .filter(|file| !old.lines.contains_key(*file))
+        .map(String::as_str)
+        .collect();
+    let ids_of = |tree: &'a TreeIds, file: &str| {
+        (tree.ids.iter())
+            .filter(|id| id.file == file)
+            .map(|id| id.id.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    let mut pairs: Vec<(&str, &str)> = vec![];
+    for old_file in gone.iter() {
+        let old_ids = ids_of(old, old_file);
+        let shared = created.iter().find(|new_file| {
+            !pairs.iter().any(|(_, paired)| paired == *new_file)
+                && ids_of(new, new_file).iter().any(|id| old_ids.contains(id))
+        });
+        if let Some(new_file) = shared {
+            pairs.push((old_file, new_file));
+        }
+    }
+    for old_file in gone.iter() {
+        if pairs.iter().any(|(paired, _)| paired == old_file) {
+            continue;
+        }
+        let dir = directory(old_file);
+        let old_files = (gone.iter())
+            .filter(|file| {
+                directory(file) == dir && !pairs.iter().any(|(paired, _)| paired == *file)
+            })
+            .count();
+        let new_files: Vec<&str> = (created.iter())
+            .filter(|file| {
+                directory(file) == dir && !pairs.iter().any(|(_, paired)| paired == *file)
+            })
+            .copied()
+            .collect();
+        if let (1, [new_file]) = (old_files, new_files.as_slice()) {
+            pairs.push((old_file, new_file));
+        }
+    }
+    pairs
+}
+
+pub fn diff_ids(old: &TreeIds, new: &TreeIds) -> IdDiff {
+    let removed: Vec<TestId> = (old.ids.iter())
+        .filter(|old_id| !new.ids.iter().any(|new_id| new_id.id == old_id.id))
+        .cloned()
+        .collect();
+    let added: Vec<TestId> = (new.ids.iter())
+        .filter(|new_id| !old.ids.iter().any(|old_id| old_id.id == new_id.id))
+        .cloned()
+        .collect();
+    let files = renamed_files(old, new);
+
+    // a removed and an added id at about the same line of the same or a renamed file are a rename,
+    // the closest lines first
+    let mut candidates: Vec<(usize, usize, usize)> = vec![];
+    for (i, old_id) in removed.iter().enumerate() {
+        for (j, new_id) in added.iter().enumerate() {
+            let same_file = old_id.file == new_id.file
+                || files.contains(&(old_id.file.as_str(), new_id.file.as_str()));
+            let distance = old_id.line.abs_diff(new_id.line);
+            if same_file && distance <= rename_window(old, new, &old_id.file, &new_id.file) {
+                candidates.push((distance, i, j));
+            }
+        }
+    }
+    candidates.sort();
+
+    let mut pairs: Vec<(usize, usize)> = vec![];
+    for (_, i, j) in candidates {
+        if !pairs.iter().any(|(pi, pj)| *pi == i || *pj == j) {
+            pairs.push((i, j));
+        }
+    }
+    pairs.sort();
+
+    let renamed = pairs
+        .iter()
+        .map(|(i, j)| (removed[*i].clone(), added[*j].clone()))
+        .collect();
+    let removed = (removed.into_iter().enumerate())
+        .filter(|(i, _)| !pairs.iter().any(|(pi, _)| pi == i))
+        .map(|(_, id)| id)
+        .collect();
+    let added = (added.into_iter().enumerate())
+        .filter(|(j, _)| !pairs.iter().any(|(_, pj)| pj == j))
+        .map(|(_, id)| id)
+        .collect();
+
+    IdDiff {
+        added,
+        removed,
+        renamed,
+    }
+}
+
+impl IdDiff {
+    /**
+     * Whether selectors of the old ids break
+     */
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.renamed.is_empty()
+    }
+
+    /**
+     * Markdown to post in a pull request
+     */
+    pub fn to_markdown(&self) -> String {
+        let mut report = format!(
+            "### Test ids\n{} renamed, {} removed, {} added\n",
+            self.renamed.len(),
+            self.removed.len(),
+            self.added.len()
+        );
+        if self.renamed.is_empty() && self.removed.is_empty() && self.added.is_empty() {
+            return report;
+        }
+
+        report.push_str("\n| change | id | file |\n| --- | --- | --- |\n");
+        for (old, new) in self.renamed.iter() {
+            report.push_str(&format!(
+                "| renamed | `{}` → `{}` | {}:{} |\n",
+                old.id, new.id, new.file, new.line
+            ));
+        }
+        for id in self.removed.iter() {
+            report.push_str(&format!(
+                "| removed | `{}` | {}:{} |\n",
+                id.id, id.file, id.line
+            ));
+        }
+        for id in self.added.iter() {
+            report.push_str(&format!(
+                "| added | `{}` | {}:{} |\n",
+                id.id, id.file, id.line
+            ));
+        }
+        report
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "renamed": self
+                .renamed
+                .iter()
+                .map(|(old, new)| json!({ "from": old.to_json(), "to": new.to_json() }))
+                .collect::<Vec<_>>(),
+            "removed": self.removed.iter().map(TestId::to_json).collect::<Vec<_>>(),
+            "added": self.added.iter().map(TestId::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::parse_source;
+
+    fn ids_of(src: &str) -> Vec<TestId> {
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            ..Default::default()
+        };
+        let mut file = parse_source(Path::new("src/App.tsx"), src.to_string()).unwrap();
+        transform_file(&mut file, &config).unwrap().unwrap();
+        collect_ids(&file, "data-testid", "src/App.tsx")
+    }
+
+    fn test_id(id: &str, file: &str, line: usize) -> TestId {
+        TestId {
+            id: id.to_string(),
+            file: file.to_string(),
+            line,
+        }
+    }
+
+    fn tree(ids: Vec<TestId>, lines: &[(&str, usize)]) -> TreeIds {
+        TreeIds {
+            ids,
+            lines: (lines.iter())
+                .map(|(file, lines)| (file.to_string(), *lines))
+                .collect(),
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn collects_ids() {
+        let src = r#"const UserCard = () => (
+  <div><img data-testid="avatar" /></div>
+);
+const Header = () => React.createElement("header", null);
+"#;
+        assert_eq!(
+            ids_of(src),
+            vec![
+                test_id("user-card", "src/App.tsx", 2),
+                test_id("avatar", "src/App.tsx", 2),
+                test_id("header", "src/App.tsx", 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn added_removed_and_renamed() {
+        let old = tree(
+            vec![
+                test_id("user-card", "src/Cards.tsx", 1),
+                test_id("footer", "src/Cards.tsx", 8),
+                test_id("nav", "src/Nav.tsx", 1),
+            ],
+            &[("src/Cards.tsx", 20), ("src/Nav.tsx", 10)],
+        );
+        let new = tree(
+            vec![
+                test_id("profile-card", "src/Cards.tsx", 2),
+                // moved to another file, which keeps selectors working
+                test_id("nav", "src/Layout.tsx", 5),
+                test_id("header", "src/Header.tsx", 1),
+            ],
+            &[
+                ("src/Cards.tsx", 20),
+                ("src/Layout.tsx", 10),
+                ("src/Header.tsx", 10),
+            ],
+        );
+        let diff = diff_ids(&old, &new);
+        assert_eq!(
+            diff,
+            IdDiff {
+                added: vec![test_id("header", "src/Header.tsx", 1)],
+                removed: vec![test_id("footer", "src/Cards.tsx", 8)],
+                renamed: vec![(
+                    test_id("user-card", "src/Cards.tsx", 1),
+                    test_id("profile-card", "src/Cards.tsx", 2)
+                )],
+            }
+        );
+        assert!(diff.is_breaking());
+        assert_eq!(
+            diff.to_markdown(),
+            "### Test ids\n1 renamed, 1 removed, 1 added\n\n| change | id | file |\n| --- | --- | --- |\n\
+             | renamed | `user-card` → `profile-card` | src/Cards.tsx:2 |\n\
+             | removed | `footer` | src/Cards.tsx:8 |\n\
+             | added | `header` | src/Header.tsx:1 |\n"
+        );
+        assert_eq!(
+            diff.to_json()["renamed"][0]["to"],
+            json!({ "id": "profile-card", "file": "src/Cards.tsx", "line": 2 })
+        );
+
+        assert!(!diff_ids(&old, &old).is_breaking());
+    }
+
+    #[test]
+    fn unrelated_ids_of_a_file() {
+        // Header was deleted and Footer added far below, which is not a rename
+        let old = tree(
+            vec![test_id("header", "src/Layout.tsx", 1)],
+            &[("src/Layout.tsx", 210)],
+        );
+        let new = tree(
+            vec![test_id("footer", "src/Layout.tsx", 201)],
+            &[("src/Layout.tsx", 210)],
+        );
+        assert_eq!(
+            diff_ids(&old, &new),
+            IdDiff {
+                added: vec![test_id("footer", "src/Layout.tsx", 201)],
+                removed: vec![test_id("header", "src/Layout.tsx", 1)],
+                renamed: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn window_of_a_long_file() {
+        // a component of a long file moved by more lines than the minimum window
+        let old = tree(
+            vec![test_id("user-card", "src/Cards.tsx", 100)],
+            &[("src/Cards.tsx", 400)],
+        );
+        let new = tree(
+            vec![test_id("profile-card", "src/Cards.tsx", 130)],
+            &[("src/Cards.tsx", 400)],
+        );
+        assert_eq!(diff_ids(&old, &new).renamed.len(), 1);
+    }
+
+    #[test]
+    fn renamed_with_its_file() {
+        let old = tree(
+            vec![
+                test_id("user-card", "src/cards/UserCard.tsx", 3),
+                test_id("nav", "src/layout/Nav.tsx", 3),
+                test_id("nav-link", "src/layout/Nav.tsx", 4),
+            ],
+            &[("src/cards/UserCard.tsx", 10), ("src/layout/Nav.tsx", 10)],
+        );
+        let new = tree(
+            vec![
+                // the only gone and new file of the directory
+                test_id("profile-card", "src/cards/ProfileCard.tsx", 3),
+                // files which share an id
+                test_id("nav", "src/layout/Navigation.tsx", 3),
+                test_id("navigation-link", "src/layout/Navigation.tsx", 4),
+                test_id("footer", "src/layout/Footer.tsx", 4),
+            ],
+            &[
+                ("src/cards/ProfileCard.tsx", 10),
+                ("src/layout/Navigation.tsx", 10),
+                ("src/layout/Footer.tsx", 10),
+            ],
+        );
+        assert_eq!(
+            diff_ids(&old, &new),
+            IdDiff {
+                added: vec![test_id("footer", "src/layout/Footer.tsx", 4)],
+                removed: vec![],
+                renamed: vec![
+                    (
+                        test_id("user-card", "src/cards/UserCard.tsx", 3),
+                        test_id("profile-card", "src/cards/ProfileCard.tsx", 3)
+                    ),
+                    (
+                        test_id("nav-link", "src/layout/Nav.tsx", 4),
+                        test_id("navigation-link", "src/layout/Navigation.tsx", 4)
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn skips_unparsable_files() {
+        let dir = std::env::temp_dir().join(format!("swc-test-plugin-diff-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/App.tsx"), "const App = () => (<main />);\n").unwrap();
+        std::fs::write(
+            dir.join("src/Broken.tsx"),
+            "const Broken = () => (<main>;\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            ..Default::default()
+        };
+        let tree = tree_ids(&dir, &config).unwrap();
+        assert_eq!(tree.ids, vec![test_id("app", "src/App.tsx", 1)]);
+        assert_eq!(tree.errors.len(), 1);
+        assert!(tree.errors[0].contains("Broken.tsx"), "{:?}", tree.errors);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrayLit, CallExpr, Callee, Expr, ExprOrSpread, Invalid, KeyValueProp, Lit, MemberExpr,
+        MemberProp, ObjectLit, Prop, PropName, PropOrSpread, SeqExpr, SpreadElement, Str,
+    },
+};
+
+use crate::assignment::str_lit;
+
+/**
+ * Functions which create an element like JSX does:
+ * React.createElement(type, props, ...children) and the automatic runtime, jsx(type, props)
+ */
+pub const RUNTIME_FACTORIES: [&str; 8] = [
+    "createElement",
+    "React.createElement",
+    "jsx",
+    "jsxs",
+    "jsxDEV",
+    "_jsx",
+    "_jsxs",
+    "_jsxDEV",
+];
+
+/**
+ * Name of the called function as written, with the objects of member calls
+ * createElement(...)             -> "createElement"
+ * React.createElement(...)       -> "React.createElement"
+ * document.createElement(...)    -> "document.createElement"
+ * (0, _jsxRuntime.jsx)(...)      -> "jsx" (how CommonJS output calls the imported function)
+ * doc(ref).createElement(...)    -> None
+ */
+fn callee_name(callee: &Expr) -> Option<String> {
+    match callee {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(prop),
+            ..
+        }) => Some(format!("{}.{}", callee_name(obj)?, prop.sym)),
+        Expr::Paren(paren) => callee_name(&paren.expr),
+        Expr::Seq(SeqExpr { exprs, .. }) => match &**exprs.last()? {
+            Expr::Member(MemberExpr {
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => Some(prop.sym.to_string()),
+            expr => callee_name(expr),
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Whether the call creates an element with one of the factories.
+ * Factories without a dot only match functions called by their names,
+ * so that `doc.createElement()` of the DOM and `crypto.h()` are left alone.
+ */
+pub fn is_element_call(call: &CallExpr, factories: &[String]) -> bool {
+    match &call.callee {
+        Callee::Expr(callee) => callee_name(callee).is_some_and(|name| factories.contains(&name)),
+        _ => false,
+    }
+}
+
+/**
+ * Whether the expression is an element call, with or without parens
+ */
+pub fn is_element_expr(expr: &Expr, factories: &[String]) -> bool {
+    match expr {
+        Expr::Call(call) => is_element_call(call, factories),
+        Expr::Paren(paren) => is_element_expr(&paren.expr, factories),
+        _ => false,
+    }
+}
+
+/**
+ * Name of the element type of an element call expression, "" for a type without a name
+ * h("div")          -> Some("div")
+ * h(components[i])  -> Some("")
+ * title             -> None
+ */
+pub fn element_expr_name(expr: &Expr, factories: &[String]) -> Option<String> {
+    match expr {
+        Expr::Call(call) if is_element_call(call, factories) => {
+            Some(element_type_name(call).unwrap_or_default())
+        }
+        Expr::Paren(paren) => element_expr_name(&paren.expr, factories),
+        _ => None,
+    }
+}
+
+/**
+ * Name of the element type, the first argument
+ * "div"            -> "div"
+ * Fragment         -> "Fragment"
+ * React.Fragment   -> "React.Fragment"
+ * _Fragment        -> "Fragment" (imported by the automatic runtime)
+ */
+pub fn element_type_name(call: &CallExpr) -> Option<String> {
+    fn expr_name(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Ident(ident) => Some(ident.sym.trim_start_matches('_').to_string()),
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => Some(format!("{}.{}", expr_name(obj)?, prop.sym)),
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            _ => None,
+        }
+    }
+
+    let arg = call.args.first()?;
+    if arg.spread.is_some() {
+        return None;
+    }
+    expr_name(&arg.expr)
+}
+
+pub fn prop_key_is(key: &PropName, name: &str) -> bool {
+    match key {
+        PropName::Ident(ident) => &*ident.sym == name,
+        PropName::Str(s) => &*s.value == name,
+        _ => false,
+    }
+}
+
+/**
+ * Whether the second argument is the props, not the children of h(type, children) of Vue
+ */
+fn has_props_arg(call: &CallExpr) -> bool {
+    match call.args.get(1) {
+        Some(ExprOrSpread { spread: None, expr }) => !matches!(
+            &**expr,
+            Expr::Array(_) | Expr::Lit(Lit::Str(_)) | Expr::Tpl(_)
+        ),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/**
+ * The props object literal, the second argument
+ */
+fn props(call: &CallExpr) -> Option<&ObjectLit> {
+    match call.args.get(1) {
+        Some(ExprOrSpread { spread: None, expr }) => match &**expr {
+            Expr::Object(object) => Some(object),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Some if the props have the key, with the value if it is a string literal
+ */
+pub fn prop_value<'a>(call: &'a CallExpr, key: &str) -> Option<Option<&'a Str>> {
+    props(call)?.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp { key: k, value }) if prop_key_is(k, key) => match &**value
+            {
+                Expr::Lit(Lit::Str(s)) => Some(Some(s)),
+                _ => Some(None),
+            },
+            Prop::Shorthand(ident) if &*ident.sym == key => Some(None),
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    })
+}
+
+/**
+ * Add `"key": value` to the props, before the other props if `first`
+ * createElement("div", { id })    -> createElement("div", { id, "key": "value" })
+ * createElement("div", { ...p })  -> createElement("div", { "key": "value", ...p }) if first
+ * createElement("div", null)      -> createElement("div", { "key": "value" })
+ * createElement("div")            -> createElement("div", { "key": "value" })
+ * jsx("div", props)               -> jsx("div", { "key": "value", ...props })
+ * h("div", [children])            -> h("div", { "key": "value" }, [children])
+ */
+pub fn add_prop(call: &mut CallExpr, key: &str, value: Expr, first: bool) {
+    let prop = PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Str(str_lit(key)),
+        value: Box::new(value),
+    })));
+    let object = |props| {
+        Box::new(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        }))
+    };
+
+    if call.args.is_empty() {
+        return;
+    }
+    if !has_props_arg(call) {
+        call.args.insert(
+            1,
+            ExprOrSpread {
+                spread: None,
+                expr: object(vec![prop]),
+            },
+        );
+        return;
+    }
+
+    let arg = &mut call.args[1];
+    if arg.spread.is_some() {
+        return;
+    }
+    match &mut *arg.expr {
+        Expr::Object(object) if first => object.props.insert(0, prop),
+        Expr::Object(object) => object.props.push(prop),
+        Expr::Lit(Lit::Null(_)) => arg.expr = object(vec![prop]),
+        // props from the spread win, like an attribute before {...props}
+        _ => {
+            let props = std::mem::replace(
+                &mut arg.expr,
+                Box::new(Expr::Invalid(Invalid { span: DUMMY_SP })),
+            );
+            arg.expr = object(vec![
+                prop,
+                PropOrSpread::Spread(SpreadElement {
+                    dot3_token: DUMMY_SP,
+                    expr: props,
+                }),
+            ]);
+        }
+    }
+}
+
+/**
+ * Remove the keys from the props object literal
+ */
+pub fn remove_props(call: &mut CallExpr, keys: &[&str]) {
+    if let Some(ExprOrSpread { spread: None, expr }) = call.args.get_mut(1) {
+        if let Expr::Object(object) = &mut **expr {
+            object.props.retain(|prop| match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::KeyValue(KeyValueProp { key, .. }) => {
+                        !keys.iter().any(|k| prop_key_is(key, k))
+                    }
+                    _ => true,
+                },
+                PropOrSpread::Spread(_) => true,
+            });
+        }
+    }
+}
+
+/**
+ * Children taken out of an element call:
+ * the arguments after the props (createElement) and `children` of the props (jsx runtime)
+ */
+pub struct Children {
+    args: Vec<ExprOrSpread>,
+    prop: Option<Box<Expr>>,
+}
+
+impl Children {
+    pub fn take(call: &mut CallExpr) -> Self {
+        let first_child = if has_props_arg(call) { 2 } else { 1 };
+        let args = if call.args.len() > first_child {
+            call.args.split_off(first_child)
+        } else {
+            vec![]
+        };
+        let prop = children_prop(call).map(|value| {
+            std::mem::replace(value, Box::new(Expr::Invalid(Invalid { span: DUMMY_SP })))
+        });
+        Self { args, prop }
+    }
+
+    /**
+     * Each child, with the elements of `children: [...]` one by one
+     */
+    pub fn exprs_mut(&mut self) -> Vec<&mut Expr> {
+        let mut exprs: Vec<&mut Expr> = vec![];
+        for arg in self.args.iter_mut() {
+            match &mut *arg.expr {
+                // h(Fragment, null, [h("main"), ...])
+                Expr::Array(ArrayLit { elems, .. }) => {
+                    exprs.extend(elems.iter_mut().flatten().map(|elem| &mut *elem.expr))
+                }
+                expr => exprs.push(expr),
+            }
+        }
+        match self.prop.as_deref_mut() {
+            Some(Expr::Array(ArrayLit { elems, .. })) => {
+                exprs.extend(elems.iter_mut().flatten().map(|elem| &mut *elem.expr))
+            }
+            Some(expr) => exprs.push(expr),
+            None => (),
+        }
+        exprs
+    }
+
+    pub fn restore(self, call: &mut CallExpr) {
+        call.args.extend(self.args);
+        if let (Some(prop), Some(value)) = (self.prop, children_prop(call)) {
+            *value = prop;
+        }
+    }
+}
+
+fn children_prop(call: &mut CallExpr) -> Option<&mut Box<Expr>> {
+    let Some(ExprOrSpread { spread: None, expr }) = call.args.get_mut(1) else {
+        return None;
+    };
+    let Expr::Object(object) = &mut **expr else {
+        return None;
+    };
+    object.props.iter_mut().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &mut **prop {
+            Prop::KeyValue(KeyValueProp { key, value }) if prop_key_is(key, "children") => {
+                Some(value)
+            }
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    })
+}
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrowExpr, BlockStmtOrExpr, Expr, Ident, JSXAttr, JSXAttrName, JSXAttrOrSpread,
+        JSXAttrValue, JSXClosingElement, JSXElement, JSXElementChild, JSXElementName, JSXExpr,
+        JSXExprContainer, JSXOpeningElement, Lit, Pat,
+    },
+};
+
+use crate::{assignment::str_lit, runtime::RuntimeNames};
+
+/**
+ * Ids which encode the parent components
+ *
+ * off:     <SubmitButton /> is left alone, its root gets "submit-button"
+ * file:    <SubmitButton data-testid="checkout-form__submit-button" /> in CheckoutForm,
+ *          and roots get their ids before the spread props, so the id from the parent wins
+ * runtime: roots are wrapped in <TestIdScope id="submit-button">{(id) => ...}</TestIdScope>
+ *          of runtimeModule, which joins the ids of the scopes in the render tree
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hierarchy {
+    #[default]
+    Off,
+    File,
+    Runtime,
+}
+
+/**
+ * Component imported from runtimeModule
+ */
+pub const SCOPE_COMPONENT: &str = "TestIdScope";
+
+/**
+ * The value of the root attribute in a scope, the parameter of the render function
+ */
+pub fn scope_id(names: &RuntimeNames) -> Expr {
+    Expr::Ident(Ident::new(names.scope_param.clone(), DUMMY_SP))
+}
+
+/**
+ * <_TestIdScope id="user-card">{(_testIdScope) => root}</_TestIdScope>
+ */
+pub fn wrap_in_scope(root: &mut JSXElement, id: &str, names: &RuntimeNames) {
+    let name = || JSXElementName::Ident(Ident::new(names.scope.clone(), DUMMY_SP));
+    let mut scope = JSXElement {
+        span: DUMMY_SP,
+        opening: JSXOpeningElement {
+            span: DUMMY_SP,
+            name: name(),
+            attrs: vec![JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span: DUMMY_SP,
+                name: JSXAttrName::Ident(Ident::new("id".into(), DUMMY_SP)),
+                value: Some(JSXAttrValue::Lit(Lit::Str(str_lit(id)))),
+            })],
+            self_closing: false,
+            type_args: None,
+        },
+        children: vec![],
+        closing: Some(JSXClosingElement {
+            span: DUMMY_SP,
+            name: name(),
+        }),
+    };
+    std::mem::swap(root, &mut scope);
+
+    let render = Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        params: vec![Pat::Ident(
+            Ident::new(names.scope_param.clone(), DUMMY_SP).into(),
+        )],
+        body: BlockStmtOrExpr::Expr(Box::new(Expr::JSXElement(Box::new(scope)))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    });
+    root.children
+        .push(JSXElementChild::JSXExprContainer(JSXExprContainer {
+            span: DUMMY_SP,
+            expr: JSXExpr::Expr(Box::new(render)),
+        }));
+}
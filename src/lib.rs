@@ -1,49 +1,155 @@
-use serde::Deserialize;
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::path::PathBuf;
+
 use serde_json::Value;
 use swc_core::{
-    common::{FileName, DUMMY_SP},
+    common::{errors::SourceMapper, sync::Lrc, FileName, Span, DUMMY_SP},
     ecma::{
-        ast::{FnDecl, Id, Ident, JSXAttrValue, Lit, Pat, Program, Stmt},
-        atoms::JsWordStaticSet,
+        ast::{FnDecl, Ident, JSXAttrValue, Lit, Pat, Program, ReturnStmt, Stmt},
+        atoms::JsWord,
         transforms::testing::test,
-        visit::{as_folder, FoldWith, VisitMut, VisitMutWith},
+        visit::{VisitMut, VisitMutWith},
     },
     plugin::{
-        metadata::TransformPluginMetadataContextKind, plugin_transform,
+        errors::HANDLER, metadata::TransformPluginMetadataContextKind, plugin_transform,
         proxies::TransformPluginProgramMetadata,
     },
 };
 
+mod assignment;
+mod check;
+#[cfg(any(feature = "cli", test))]
+pub mod codemod;
+mod component;
+mod config;
+#[cfg(any(feature = "cli", test))]
+pub mod diff;
+mod element_call;
+mod hierarchy;
+mod lockfile;
+mod path;
+mod preset;
+mod route;
+#[cfg(any(feature = "cli", test))]
+pub mod runner;
+mod runtime;
+mod styled;
+#[cfg(any(feature = "cli", test))]
+pub mod xref;
+
+pub use check::{CheckDiagnostic, CheckLevel};
+pub use component::{ComponentDetection, HelperFunctions, RenderProps};
+pub use config::{Config, Mode};
+pub use hierarchy::Hierarchy;
+pub use preset::{CaseStyle, Preset};
+pub use route::RouteIds;
+pub use runtime::ValueMode;
+pub use styled::Styled;
+
+/**
+ * Add the test id attribute to the root element of each component.
+ *
+ * Running the visitor again on its own output (or on the printed and re-parsed output)
+ * changes nothing: roots which already have the attribute are skipped whatever its value is,
+ * and `lazy-load="true"` is left as it is.
+ */
+#[derive(Clone)]
 pub struct TransformVisitor {
     attr_name: String,
     ignore_components: Vec<String>,
+    preset: Preset,
+    id_template: String,
+    mode: Mode,
+    check_level: CheckLevel,
+    component_detection: ComponentDetection,
+    helper_functions: HelperFunctions,
+    render_props: RenderProps,
+    styled: Styled,
+    display_name: bool,
+    /// roots whose first host child gets the attribute instead, e.g. Fragment
+    transparent_elements: Vec<String>,
+    /// functions which create an element, e.g. createElement
+    element_factories: Vec<String>,
+    /// id of the Next.js route which the file renders, e.g. "page-users-id"
+    route_id: Option<String>,
+    /// the default export of the file, which gets route_id
+    route_component: Option<JsWord>,
+    value_mode: ValueMode,
+    runtime_module: String,
+    /// names to import from runtimeModule, e.g. testId of valueMode "runtime"
+    runtime_imports: Vec<&'static str>,
+    /// local names of the runtime imports in the file
+    runtime_names: RuntimeNames,
+    hierarchy: Hierarchy,
+    /// id of the root being visited, which is wrapped in TestIdScope
+    scope: Option<String>,
+    lockfile: Option<PathBuf>,
+    update_lockfile: bool,
+    /// ids of the components of the file in the lockfile
+    locked_ids: FileIds,
+    /// ids of the components found in the file
+    component_ids: FileIds,
+    source_location: bool,
+    source_map: Option<Lrc<dyn SourceMapper>>,
+    diagnostics: Vec<CheckDiagnostic>,
     filename: FileName,
     is_in_child: bool,
-    parent_id: Id,
-    component_name: Ident,
-}
-#[derive(Debug, Default, Clone, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct Config {
-    #[serde(default)]
-    pub attr_name: String,
-    pub ignore_components: Vec<String>,
+    /// lexically enclosing functions, innermost last
+    /// (None for functions which are not components)
+    components: Vec<Option<ComponentScope>>,
+    /// render props found so far in the current component
+    render_count: usize,
+    /// variable of the object literal being visited, e.g. a Storybook story
+    object_name: Option<Ident>,
+    /// assignments to insert after the statement being visited
+    assignments: Vec<Assignment>,
 }
 
-use convert_case::{Case, Casing};
+use assignment::{str_lit, Assignment, StmtLike};
+use component::{calls_hook, is_component_wrapper, is_pascal_case, ComponentScope};
+use element_call::{
+    add_prop, element_expr_name, element_type_name, is_element_call, is_element_expr, prop_value,
+    remove_props, Children, RUNTIME_FACTORIES,
+};
+use hierarchy::{scope_id, wrap_in_scope, SCOPE_COMPONENT};
+use lockfile::{follow_rename, lockfile_path, read_file_ids, write_file_ids, FileIds};
+use route::route_id;
+use runtime::{helper_call, helper_value, insert_runtime_import, RuntimeNames, RUNTIME_HELPER};
 use string_cache::Atom;
+use styled::{add_attrs, default_props, styled_tag};
 use swc_core::ecma::ast::{
-    BlockStmt, BlockStmtOrExpr, Expr, JSXAttr, JSXAttrName, JSXAttrOrSpread, JSXClosingElement,
-    JSXElementName, JSXOpeningElement, Str, VarDecl,
+    BinaryOp, BlockStmt, BlockStmtOrExpr, CallExpr, Callee, DefaultDecl, ExportDefaultDecl,
+    ExportDefaultExpr, ExportNamedSpecifier, ExportSpecifier, Expr, ExprOrSpread, FnExpr, JSXAttr,
+    JSXAttrName, JSXAttrOrSpread, JSXElement, JSXElementChild, JSXElementName, JSXExpr,
+    JSXExprContainer, JSXFragment, JSXObject, JSXOpeningElement, KeyValueProp, MethodProp, Module,
+    ModuleDecl, ModuleExportName, ModuleItem, NamedExport, Prop, PropName, Script, Str,
+    VarDeclarator,
 };
 
 /**
- * Convert to kebab-case from UpperCamelCase(component name)
+ * Attribute of `sourceLocation`
  */
-fn convert_to_kebab_case(s: Atom<JsWordStaticSet>) -> String {
-    return s.clone().to_string().to_case(Case::Kebab);
+const SOURCE_LOCATION_ATTR: &str = "data-source-location";
+
+/**
+ * Attributes which a root element already has
+ */
+#[derive(Default)]
+struct RootAttrs {
+    has_attr: bool,
+    /// hand-written value of the attribute (only string literals can be checked)
+    current_value: Option<(Span, String)>,
+    has_location: bool,
 }
 
+/**
+ * transparentElements by default: they don't render an element of their own
+ * (and Fragment only accepts `key`)
+ */
+const DEFAULT_TRANSPARENT_ELEMENTS: [&str; 4] =
+    ["Fragment", "React.Fragment", "*.Provider", "*.Consumer"];
+
 /**
  * Whether vec contains item
  * return true if one element of vec is same item(String Compare)
@@ -61,42 +167,236 @@ fn vec_contains_string(vec: Vec<String>, item: String) -> bool {
 }
 
 /**
-* Check if the expression is Parenthesis Element
-* which returns JSXElement like the following example.
-*
-* (
-    <div>
-        <Component />
-    </div>
-  )
-*/
-fn parse_expr_stmt(expr_stmt: &mut Box<Expr>) -> bool {
-    let mut is_jsx_component = false;
-
-    match &mut **expr_stmt {
-        // TODO: support for JSX***
-        // https://docs.rs/swc_ecma_ast/0.80.0/swc_ecma_ast/enum.Expr.html
-        Expr::Paren(paren_expr) => {
-            let expr = &mut paren_expr.expr;
-            // TODO: support for JSX***
-            match &mut **expr {
-                Expr::JSXElement(_) => is_jsx_component = true,
-                _ => (),
+ * Name of the jsx attribute as written in the code
+ * <div data-testid="..." />  -> "data-testid"
+ * <div test:id="..." />      -> "test:id"
+ */
+fn jsx_attr_name(name: &JSXAttrName) -> String {
+    match name {
+        JSXAttrName::Ident(ident) => ident.sym.to_string(),
+        JSXAttrName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+    }
+}
+
+/**
+ * Name of the jsx element as written in the code
+ * <div />                -> "div"
+ * <React.Fragment />     -> "React.Fragment"
+ * <svg:rect />           -> "svg:rect"
+ */
+fn jsx_element_name(name: &JSXElementName) -> String {
+    fn member_name(obj: &JSXObject, prop: &Ident) -> String {
+        match obj {
+            JSXObject::Ident(obj) => format!("{}.{}", obj.sym, prop.sym),
+            JSXObject::JSXMemberExpr(member) => {
+                format!("{}.{}", member_name(&member.obj, &member.prop), prop.sym)
+            }
+        }
+    }
+
+    match name {
+        JSXElementName::Ident(ident) => ident.sym.to_string(),
+        JSXElementName::JSXMemberExpr(member) => member_name(&member.obj, &member.prop),
+        JSXElementName::JSXNamespacedName(name) => format!("{}:{}", name.ns.sym, name.name.sym),
+    }
+}
+
+/**
+ * Whether the element name matches one of transparentElements,
+ * where "*.Provider" matches any name ending with ".Provider"
+ */
+fn is_transparent_element(transparent_elements: &[String], name: &str) -> bool {
+    transparent_elements
+        .iter()
+        .any(|pattern| match pattern.strip_prefix('*') {
+            Some(suffix) => name.ends_with(suffix) && name.len() > suffix.len(),
+            None => pattern == name,
+        })
+}
+
+/**
+ * name="value", or name={value} if it is not a string
+ */
+fn jsx_attr(name: &str, value: Expr) -> JSXAttrOrSpread {
+    let value = match value {
+        Expr::Lit(Lit::Str(s)) => JSXAttrValue::Lit(Lit::Str(Str {
+            span: DUMMY_SP,
+            raw: Some(format!("\"{}\"", s.value).into()),
+            value: s.value,
+        })),
+        expr => JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            span: DUMMY_SP,
+            expr: JSXExpr::Expr(Box::new(expr)),
+        }),
+    };
+    JSXAttrOrSpread::JSXAttr(JSXAttr {
+        span: DUMMY_SP,
+        name: JSXAttrName::Ident(Ident {
+            span: DUMMY_SP,
+            sym: name.into(),
+            optional: false,
+        }),
+        value: Some(value),
+    })
+}
+
+/**
+ * Check if the expression evaluates to JSX
+ *
+ * <div />, <>...</>, (<div />)
+ * isOpen ? <Modal /> : <Button />
+ * isOpen && <Modal />, error || <Form />
+ * React.createElement("div"), _jsx("div", {})
+ */
+fn is_jsx_expr(expr: &Expr, factories: &[String]) -> bool {
+    match expr {
+        Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+        Expr::Paren(paren_expr) => is_jsx_expr(&paren_expr.expr, factories),
+        Expr::Cond(cond_expr) => {
+            is_jsx_expr(&cond_expr.cons, factories) || is_jsx_expr(&cond_expr.alt, factories)
+        }
+        Expr::Bin(bin_expr) => match bin_expr.op {
+            BinaryOp::LogicalAnd => is_jsx_expr(&bin_expr.right, factories),
+            BinaryOp::LogicalOr | BinaryOp::NullishCoalescing => {
+                is_jsx_expr(&bin_expr.left, factories) || is_jsx_expr(&bin_expr.right, factories)
+            }
+            _ => false,
+        },
+        expr => is_element_expr(expr, factories),
+    }
+}
+
+/**
+ * Check if the expression is a function,
+ * and return whether it returns JSXElement and whether it calls a hook (only if require_hooks)
+ *
+ * () => { return <div /> }
+ * () => (<div />)
+ * function () { return <div /> }
+ * memo(() => (<div />))
+ */
+fn inspect_function(
+    expr: &Expr,
+    require_hooks: bool,
+    factories: &[String],
+) -> Option<(bool, bool)> {
+    match expr {
+        // https://swc.rs/docs/plugin/ecmascript/cheatsheet#matching-boxt
+        Expr::Arrow(arrow_expr) => {
+            let has_hook = require_hooks && calls_hook(&arrow_expr.body);
+            let returns_jsx = match &arrow_expr.body {
+                // Same as Functions Expression
+                BlockStmtOrExpr::BlockStmt(block_stmt) => parse_block_stmt(block_stmt, factories),
+                BlockStmtOrExpr::Expr(expr) => is_jsx_expr(expr, factories),
+            };
+            Some((returns_jsx, has_hook))
+        }
+        // return fn expr which returns JSXElement
+        Expr::Fn(fn_expr) => {
+            let has_hook = require_hooks && calls_hook(&fn_expr.function.body);
+            // Same as Arrow Functions
+            let returns_jsx = (fn_expr.function.body.as_ref())
+                .is_some_and(|body| parse_block_stmt(body, factories));
+            Some((returns_jsx, has_hook))
+        }
+        // the function wrapped in memo() or forwardRef()
+        Expr::Call(call_expr) if is_component_wrapper(&call_expr.callee) => {
+            match call_expr.args.first() {
+                Some(ExprOrSpread { spread: None, expr }) => {
+                    inspect_function(expr, require_hooks, factories)
+                }
+                _ => None,
             }
         }
-        _ => (),
+        _ => None,
+    }
+}
+
+/**
+ * Name of the component exported as default
+ *
+ * export default function Page() { ... }
+ * export default Page
+ * export { Page as default }
+ */
+fn default_export_name(module: &Module) -> Option<JsWord> {
+    module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+            decl: DefaultDecl::Fn(FnExpr {
+                ident: Some(ident), ..
+            }),
+            ..
+        })) => Some(ident.sym.clone()),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+            expr, ..
+        })) => match &**expr {
+            Expr::Ident(ident) => Some(ident.sym.clone()),
+            _ => None,
+        },
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+            specifiers,
+            src: None,
+            ..
+        })) => specifiers.iter().find_map(|specifier| match specifier {
+            ExportSpecifier::Named(ExportNamedSpecifier {
+                orig: ModuleExportName::Ident(orig),
+                exported: Some(ModuleExportName::Ident(exported)),
+                ..
+            }) if &*exported.sym == "default" => Some(orig.sym.clone()),
+            _ => None,
+        }),
+        _ => None,
+    })
+}
+
+/**
+ * Check if the expression is an object whose `render` is named by the variable
+ *
+ * { args: {...}, render: () => <Button /> }           (a Storybook story)
+ * defineComponent({ render() { return h("div") } })   (a Vue component)
+ */
+fn is_component_object(expr: &Expr) -> bool {
+    match expr {
+        Expr::Object(_) => true,
+        Expr::Call(call_expr) => {
+            matches!(&call_expr.callee, Callee::Expr(callee)
+                if matches!(&**callee, Expr::Ident(ident) if &*ident.sym == "defineComponent"))
+                && matches!(call_expr.args.first(), Some(ExprOrSpread { spread: None, expr })
+                    if matches!(&**expr, Expr::Object(_)))
+        }
+        _ => false,
     }
+}
 
-    return is_jsx_component;
+/**
+ * Check if the expression is a function which returns JSXElement (a render prop)
+ *
+ * () => <Page />
+ * (form) => (<form />)
+ * function () { return <div /> }
+ */
+fn is_render_function(expr: &Expr, factories: &[String]) -> bool {
+    match expr {
+        Expr::Arrow(arrow_expr) => match &arrow_expr.body {
+            BlockStmtOrExpr::BlockStmt(block_stmt) => parse_block_stmt(block_stmt, factories),
+            BlockStmtOrExpr::Expr(expr) => is_jsx_expr(expr, factories),
+        },
+        Expr::Fn(fn_expr) => {
+            (fn_expr.function.body.as_ref()).is_some_and(|body| parse_block_stmt(body, factories))
+        }
+        _ => false,
+    }
 }
 
 /**
- * Check if the block statement is like following examples.
+ * Check if any return statement of the block returns JSX,
+ * including the ones nested in if, switch, try and loops (but not in nested functions)
  *
- * <<Pattern 1 (Self Closing)>>
+ * <<Pattern 1 (Return JSX)>>
  * return <Component />
+ * return isOpen ? <Modal /> : null
  *
- * <<Pattern 2 (Return JSXElement with Parenthesis)>>
+ * <<Pattern 2 (Return JSX with Parenthesis)>>
  * return (
  *   <div>
  *     <h1>Text</h1>
@@ -104,323 +404,1076 @@ fn parse_expr_stmt(expr_stmt: &mut Box<Expr>) -> bool {
  * )
  *
  * <<Pattern 3 (Return JSXElement without Parenthesis)>>
- * return <div><h1>Text</h1></div>
+ * return
+ *   <div><h1>Text</h1></div>
  *
+ * <<Pattern 4 (Return an element call)>>
+ * return React.createElement("div", null)
  */
-fn parse_block_stmt(block_stmt: &mut BlockStmt) -> bool {
-    let mut is_jsx_component = false;
-
-    let stmts = &mut block_stmt.stmts;
-    for stmt in stmts.iter_mut() {
-        // check type of arg
-        if let Stmt::Return(return_stmt) = stmt {
-            if let Some(arg) = &mut return_stmt.arg {
-                match &mut **arg {
-                    // TODO: support for JSX***
-                    // <<Pattern 1 (Self Closing)>>
-                    Expr::JSXElement(_) => is_jsx_component = true,
-                    // <<Pattern 2 (Return JSXElement with Parenthesis)>>
-                    Expr::Paren(paren_expr) => {
-                        let expr = &mut paren_expr.expr;
-                        // TODO: support for JSX***
-                        match &mut **expr {
-                            Expr::JSXElement(_) => is_jsx_component = true,
-                            _ => (),
-                        }
-                    }
-                    _ => (),
-                }
-            }
-        }
+fn parse_block_stmt(block_stmt: &BlockStmt, factories: &[String]) -> bool {
+    stmts_return_jsx(&block_stmt.stmts, factories)
+}
 
-        // <<Pattern 3 (Return JSXElement without Parenthesis)>>
-        if let Stmt::Expr(expr_stmt) = stmt {
-            let expr = &mut expr_stmt.expr;
-            match &mut **expr {
-                Expr::JSXElement(_) => is_jsx_component = true,
-                _ => (),
+fn stmts_return_jsx(stmts: &[Stmt], factories: &[String]) -> bool {
+    let mut is_empty_return = false;
+    for stmt in stmts.iter() {
+        let returns_jsx = match stmt {
+            // <<Pattern 1, 2 and 4>>
+            Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => is_jsx_expr(arg, factories),
+            // <<Pattern 3>>
+            // the line break after `return` splits it into `return;` and the element
+            Stmt::Expr(expr_stmt) => {
+                is_empty_return
+                    && matches!(&*expr_stmt.expr, Expr::JSXElement(_) | Expr::JSXFragment(_))
+            }
+            Stmt::Block(block_stmt) => parse_block_stmt(block_stmt, factories),
+            Stmt::If(if_stmt) => {
+                stmt_returns_jsx(&if_stmt.cons, factories)
+                    || (if_stmt.alt.as_deref()).is_some_and(|alt| stmt_returns_jsx(alt, factories))
+            }
+            Stmt::Switch(switch_stmt) => {
+                (switch_stmt.cases.iter()).any(|case| stmts_return_jsx(&case.cons, factories))
             }
+            Stmt::Try(try_stmt) => {
+                parse_block_stmt(&try_stmt.block, factories)
+                    || (try_stmt.handler.as_ref())
+                        .is_some_and(|handler| parse_block_stmt(&handler.body, factories))
+                    || (try_stmt.finalizer.as_ref())
+                        .is_some_and(|finalizer| parse_block_stmt(finalizer, factories))
+            }
+            Stmt::Labeled(labeled_stmt) => stmt_returns_jsx(&labeled_stmt.body, factories),
+            Stmt::While(while_stmt) => stmt_returns_jsx(&while_stmt.body, factories),
+            Stmt::DoWhile(do_while_stmt) => stmt_returns_jsx(&do_while_stmt.body, factories),
+            Stmt::For(for_stmt) => stmt_returns_jsx(&for_stmt.body, factories),
+            Stmt::ForIn(for_in_stmt) => stmt_returns_jsx(&for_in_stmt.body, factories),
+            Stmt::ForOf(for_of_stmt) => stmt_returns_jsx(&for_of_stmt.body, factories),
+            _ => false,
+        };
+        if returns_jsx {
+            return true;
         }
+        is_empty_return = matches!(stmt, Stmt::Return(ReturnStmt { arg: None, .. }));
     }
+    false
+}
+
+fn stmt_returns_jsx(stmt: &Stmt, factories: &[String]) -> bool {
+    stmts_return_jsx(std::slice::from_ref(stmt), factories)
+}
 
-    return is_jsx_component;
+impl Default for TransformVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TransformVisitor {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             attr_name: "".to_string(),
             ignore_components: [].to_vec(),
+            preset: Preset::default(),
+            id_template: "{component}".to_string(),
+            mode: Mode::default(),
+            check_level: CheckLevel::default(),
+            component_detection: ComponentDetection::default(),
+            helper_functions: HelperFunctions::default(),
+            render_props: RenderProps::default(),
+            styled: Styled::default(),
+            display_name: false,
+            transparent_elements: DEFAULT_TRANSPARENT_ELEMENTS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            element_factories: RUNTIME_FACTORIES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            route_id: None,
+            route_component: None,
+            value_mode: ValueMode::default(),
+            runtime_module: String::new(),
+            runtime_imports: vec![],
+            runtime_names: RuntimeNames::default(),
+            hierarchy: Hierarchy::default(),
+            scope: None,
+            lockfile: None,
+            update_lockfile: false,
+            locked_ids: FileIds::new(),
+            component_ids: FileIds::new(),
+            source_location: false,
+            source_map: None,
+            diagnostics: vec![],
             filename: FileName::Anon,
             is_in_child: false,
-            parent_id: Id::default(),
-            component_name: Ident {
-                span: DUMMY_SP,
-                sym: "".into(),
-                optional: false,
-            },
+            components: vec![],
+            render_count: 0,
+            object_name: None,
+            assignments: vec![],
         }
     }
 
-    fn set_config(&mut self, config: &Config, filename: FileName) {
-        self.attr_name = config.attr_name.clone();
+    pub fn set_config(&mut self, config: &Config, filename: FileName) {
+        // attrName takes precedence over the attribute name of the preset
+        self.attr_name = if config.attr_name.is_empty() {
+            config.preset.attr_name().to_string()
+        } else {
+            config.attr_name.clone()
+        };
         self.ignore_components = config.ignore_components.clone();
+        self.preset = config.preset;
+        if !config.id_template.is_empty() {
+            self.id_template = config.id_template.clone();
+        }
+        self.mode = config.mode;
+        self.check_level = config.check_level;
+        self.component_detection = config.component_detection;
+        self.helper_functions = config.helper_functions;
+        self.render_props = config.render_props;
+        self.styled = config.styled;
+        self.display_name = config.display_name;
+        for element in config.transparent_elements.iter() {
+            if !self.transparent_elements.contains(element) {
+                self.transparent_elements.push(element.clone());
+            }
+        }
+        for factory in config.element_factories.iter() {
+            if !self.element_factories.contains(factory) {
+                self.element_factories.push(factory.clone());
+            }
+        }
+        self.value_mode = config.value_mode;
+        self.runtime_module = config.runtime_module.clone();
+        self.hierarchy = config.hierarchy;
+        self.update_lockfile = config.update_lockfile;
+        self.source_location = config.source_location;
+        self.route_id = match (&config.route_ids, &filename) {
+            (Some(route_ids), FileName::Real(path)) => {
+                route_id(&path.to_string_lossy(), &route_ids.roots)
+            }
+            _ => None,
+        };
         self.filename = filename;
     }
-}
 
-impl VisitMut for TransformVisitor {
-    // TODO: CHECK ignoreComponents
-    fn visit_mut_fn_decl(&mut self, n: &mut FnDecl) {
-        if !self.is_in_child {
-            self.component_name = n.ident.clone();
+    /**
+     * Source map of the file, to find the lines of `sourceLocation`
+     */
+    pub fn set_source_map(&mut self, source_map: Lrc<dyn SourceMapper>) {
+        self.source_map = Some(source_map);
+    }
+
+    /**
+     * Lockfile which the ids of the file are read from (and written to with `updateLockfile`),
+     * after set_config for the filename
+     */
+    pub fn set_lockfile(&mut self, path: PathBuf) -> Result<(), String> {
+        self.locked_ids = read_file_ids(&path, &self.filename.to_string(), self.update_lockfile)?;
+        self.lockfile = Some(path);
+        Ok(())
+    }
+
+    /**
+     * Write the ids of the file to the lockfile with `updateLockfile`, after the transform
+     */
+    pub fn write_lockfile(&self) -> Result<(), String> {
+        match (&self.lockfile, self.update_lockfile) {
+            (Some(path), true) => {
+                write_file_ids(path, &self.filename.to_string(), self.component_ids.clone())
+            }
+            _ => Ok(()),
         }
-        // check after updating self.component_name
-        n.visit_mut_children_with(self);
     }
 
-    // This function is to get component_name and check variable whether jsx component or not
-    fn visit_mut_var_decl(&mut self, n: &mut VarDecl) {
-        let decls = &mut n.decls;
-        let mut is_jsx_component = false;
-
-        for decl in decls.iter_mut() {
-            if let Some(init) = &mut decl.init {
-                // https://swc.rs/docs/plugin/ecmascript/cheatsheet#matching-boxt
-                if let Expr::Arrow(arrow_expr) = &mut **init {
-                    if let BlockStmtOrExpr::BlockStmt(block_stmt) = &mut arrow_expr.body {
-                        // Same as Functions Expression
-                        let tmp_is_jsx_component = parse_block_stmt(block_stmt);
-                        is_jsx_component = tmp_is_jsx_component;
-                    }
-                    if let BlockStmtOrExpr::Expr(expr_stmt) = &mut arrow_expr.body {
-                        let tmp_is_jsx_component = parse_expr_stmt(expr_stmt);
-                        is_jsx_component = tmp_is_jsx_component;
-                    }
-                }
+    /**
+     * Problems found in `mode: "check"`
+     */
+    pub fn take_diagnostics(&mut self) -> Vec<CheckDiagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /**
+     * Name of the innermost component, empty outside of components
+     */
+    fn component_name(&self) -> String {
+        self.components
+            .last()
+            .and_then(|component| component.as_ref())
+            .map(|component| component.name.sym.to_string())
+            .unwrap_or_default()
+    }
+
+    /**
+     * Value of the attribute for the component
+     * UserCard -> "user-card" with idTemplate "{component}"
+     */
+    fn component_id(&self, name: &str) -> String {
+        if let Some(id) = self.locked_ids.get(name) {
+            return id.clone();
+        }
+        // the page of a Next.js route is named by the route
+        let name = match (&self.route_id, &self.route_component) {
+            (Some(route_id), Some(component)) if &**component == name => route_id,
+            _ => name,
+        };
+        let component = self.preset.case_style().convert(name);
+        self.id_template.replace("{component}", &component)
+    }
+
+    /**
+     * Value of the attribute for the current component
+     * UserCard -> "user-card" with idTemplate "{component}"
+     * a render prop of UserCard -> "user-card__render-0"
+     */
+    fn render_id(&self) -> String {
+        let id = self.component_id(&self.component_name());
+        match self.components.last() {
+            Some(Some(ComponentScope {
+                render: Some(render),
+                ..
+            })) => format!("{}__{}", id, render),
+            _ => id,
+        }
+    }
+
+    /**
+     * Scope of a render prop in the current component following renderProps,
+     * None if it is disabled or outside of components
+     */
+    fn render_scope(&mut self, name: &str) -> Option<ComponentScope> {
+        let component = self.components.last()?.as_ref()?;
+        let render = match self.render_props {
+            RenderProps::Off => return None,
+            RenderProps::Index => format!("render-{}", self.render_count),
+            RenderProps::AttrName => self.preset.case_style().convert(name),
+        };
+        let scope = ComponentScope {
+            name: component.name.clone(),
+            render: Some(render),
+        };
+        self.render_count += 1;
+        Some(scope)
+    }
 
-                // return fn expr which returns JSXElement
-                if let Expr::Fn(fn_expr) = &mut **init {
-                    if let Some(block_stmt) = &mut fn_expr.function.body {
-                        // Same as Arrow Functions
-                        let tmp_is_jsx_component = parse_block_stmt(block_stmt);
-                        is_jsx_component = tmp_is_jsx_component;
+    /**
+     * Visit the render props in the attributes: `render={() => <Page />}`
+     */
+    fn visit_mut_render_props(&mut self, attrs: &mut [JSXAttrOrSpread]) {
+        for attr_or_spread in attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
+                if let Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) = &mut attr.value
+                {
+                    if is_render_function(expr, &self.element_factories) {
+                        if let Some(scope) = self.render_scope(&jsx_attr_name(&attr.name)) {
+                            self.visit_mut_scope(Some(scope), expr);
+                        }
                     }
                 }
             }
         }
+    }
 
-        if !self.is_in_child && is_jsx_component {
-            let first_decl = &mut decls[0];
-            if let Pat::Ident(ident) = &first_decl.name {
-                // get the function name
-                self.component_name = ident.id.clone();
-            }
-        }
+    /**
+     * Whether the function is a component by componentDetection
+     */
+    fn is_component(&self, name: &Ident, returns_jsx: bool, has_hook: bool) -> bool {
+        let detection = self.component_detection;
+        (!detection.pascal_case || is_pascal_case(&name.sym))
+            && (!detection.returns_jsx || returns_jsx)
+            && (!detection.require_hooks || has_hook)
+    }
 
-        // check after update self.component_name
+    /**
+     * Visit a function.
+     * JSX in it belongs to the component (None if it is not a component) until the function ends,
+     * then the enclosing component (if any) is restored.
+     */
+    fn visit_mut_scope<N: VisitMutWith<Self>>(
+        &mut self,
+        component: Option<ComponentScope>,
+        n: &mut N,
+    ) {
+        // render props are numbered through the component, including nested ones
+        let is_render = matches!(
+            &component,
+            Some(ComponentScope {
+                render: Some(_),
+                ..
+            })
+        );
+        let render_count = (!is_render).then(|| std::mem::take(&mut self.render_count));
+
+        self.components.push(component);
+        // the root of a component declared in JSX children is a root as well
+        let is_in_child = std::mem::replace(&mut self.is_in_child, false);
         n.visit_mut_children_with(self);
+        self.is_in_child = is_in_child;
+        self.components.pop();
+
+        if let Some(render_count) = render_count {
+            self.render_count = render_count;
+        }
     }
 
-    // visit jsx opening_element
-    fn visit_mut_jsx_opening_element(&mut self, n: &mut JSXOpeningElement) {
-        // CHECK: support for sef-closing component
-        if self.is_in_child {
-            return;
+    /**
+     * Visit a function declared with the name,
+     * as a component or a helper function by componentDetection
+     */
+    fn visit_mut_function<N: VisitMutWith<Self>>(
+        &mut self,
+        name: Ident,
+        returns_jsx: bool,
+        has_hook: bool,
+        n: &mut N,
+    ) {
+        if self.is_component(&name, returns_jsx, has_hook) {
+            self.lock_component(&name.sym);
+            self.visit_mut_scope(Some(ComponentScope::new(name)), n);
+        } else {
+            self.visit_mut_helper(n);
         }
+    }
 
-        let element_name = &n.name;
-        let attrs = &mut n.attrs;
-        let is_self_closing = n.self_closing;
+    /**
+     * Visit a function which is not a component, following helperFunctions
+     */
+    fn visit_mut_helper<N: VisitMutWith<Self>>(&mut self, n: &mut N) {
+        match self.helper_functions {
+            HelperFunctions::Skip => self.visit_mut_scope(None, n),
+            // JSX in it belongs to the enclosing component
+            HelperFunctions::EnclosingComponent => n.visit_mut_children_with(self),
+        }
+    }
 
-        // add "data-testid"(by default) if there is no "data-testid"(by default) attribute.
+    /**
+     * Check or transform the root element of the current component:
+     * report the problems in check mode, and return the attributes to add in transform mode
+     */
+    fn visit_root(&mut self, span: Span, existing: RootAttrs) -> Vec<(String, String)> {
         let attr_name = self.attr_name.clone();
-        let mut has_attr = false;
-        for attr_or_spread in attrs.iter_mut() {
-            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
-                if let JSXAttrName::Ident(name) = &mut attr.name {
-                    if &*name.sym == attr_name {
-                        has_attr = true;
-                    }
-                }
+        let component_name = self.component_name();
+        let is_target = !component_name.is_empty()
+            && !vec_contains_string(self.ignore_components.clone(), component_name.clone());
+        if !is_target {
+            return vec![];
+        }
+
+        // report instead of modifying the code
+        if self.mode == Mode::Check {
+            let expected = self.render_id();
+            match existing.current_value {
+                _ if !existing.has_attr => self.report(
+                    span,
+                    format!(
+                        "root element of `{}` has no {} (expected {}=\"{}\")",
+                        component_name, attr_name, attr_name, expected
+                    ),
+                ),
+                Some((span, value)) if value != expected => self.report(
+                    span,
+                    format!(
+                        "{}=\"{}\" of `{}` does not match idTemplate (expected \"{}\")",
+                        attr_name, value, component_name, expected
+                    ),
+                ),
+                _ => (),
             }
+            return vec![];
         }
 
+        let mut attrs = vec![];
         //  Check
         //  1. this element has specific attribute
         //  2. this element has component_name(is not child element)
         //  3. this element is not one of ignore components
-        if !has_attr
-            && &*self.component_name.sym != ""
-            && !vec_contains_string(
-                self.ignore_components.clone(),
-                self.component_name.sym.to_string(),
-            )
-        {
-            // add attribute
-            attrs.push(JSXAttrOrSpread::JSXAttr(JSXAttr {
-                span: DUMMY_SP,
-                name: JSXAttrName::Ident(Ident {
-                    span: DUMMY_SP,
-                    sym: attr_name.into(),
-                    optional: false,
-                }),
-                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
-                    span: DUMMY_SP,
-                    value: Atom::from(
-                        convert_to_kebab_case(self.component_name.sym.clone()).to_lowercase(),
-                    ),
-                    raw: Some(
-                        format!(
-                            "\"{}\"",
-                            convert_to_kebab_case(self.component_name.sym.clone()).to_lowercase()
-                        )
-                        .into(),
-                    ),
-                }))),
-            }));
+        if self.mode == Mode::Transform && !existing.has_attr {
+            attrs.push((attr_name, self.render_id()));
         }
+        if self.mode == Mode::Transform && self.source_location && !existing.has_location {
+            if let Some(location) = self.source_location_of(span) {
+                attrs.push((SOURCE_LOCATION_ATTR.to_string(), location));
+            }
+        }
+        attrs
+    }
 
-        for attr_or_spread in attrs.iter_mut() {
-            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
-                // almost same as visit_mut_jsx_attr(update name or value of jsx attribute) function
-                if let JSXAttrName::Ident(name) = &mut attr.name {
-                    if let Some(JSXAttrValue::Lit(value)) = &mut attr.value {
-                        if let Lit::Str(s) = value {
-                            if &*name.sym == "lazy-load" {
-                                if &*s.value == "false" {
-                                    s.span = DUMMY_SP;
-                                    s.value = Atom::from("true");
-                                    s.raw = Some("\"true\"".into());
-                                }
-                            }
-                        }
-                    }
-                }
+    /**
+     * Visit an element call (React.createElement(), jsx(), ...) like a JSX element:
+     * the props of the root get the attribute
+     */
+    fn visit_mut_element_call(&mut self, n: &mut CallExpr) {
+        if self.mode == Mode::Strip {
+            remove_props(n, &[&self.attr_name, SOURCE_LOCATION_ATTR]);
+            return n.visit_mut_children_with(self);
+        }
+
+        if !self.is_in_child {
+            let is_transparent = element_type_name(n)
+                .is_some_and(|name| is_transparent_element(&self.transparent_elements, &name));
+            if is_transparent {
+                return self.visit_mut_transparent_call(n);
+            }
+
+            let existing = RootAttrs {
+                has_attr: prop_value(n, &self.attr_name).is_some(),
+                current_value: prop_value(n, &self.attr_name)
+                    .flatten()
+                    .map(|s| (s.span, s.value.to_string())),
+                has_location: prop_value(n, SOURCE_LOCATION_ATTR).is_some(),
+            };
+            for (name, value) in self.visit_root(n.span, existing) {
+                let value = self.attr_value(&name, value);
+                // before the spread props, so that the id passed by the parent wins
+                let first = self.hierarchy == Hierarchy::File && name == self.attr_name;
+                add_prop(n, &name, value, first);
+            }
+        } else if let Some(id) = element_type_name(n).and_then(|name| self.usage_id(&name)) {
+            if prop_value(n, &self.attr_name).is_none() {
+                let value = self.attr_value(&self.attr_name.clone(), id);
+                add_prop(n, &self.attr_name.clone(), value, false);
             }
         }
 
-        // check top level of component
-        // CHECK: support for sef-closing component
-        if !is_self_closing {
-            self.is_in_child = true;
+        // elements in the arguments are children
+        let is_in_child = std::mem::replace(&mut self.is_in_child, true);
+        n.visit_mut_children_with(self);
+        self.is_in_child = is_in_child;
+    }
+
+    /**
+     * Visit an element call of transparentElements (createElement(Fragment, null, ...)):
+     * a child element is the root instead
+     */
+    fn visit_mut_transparent_call(&mut self, n: &mut CallExpr) {
+        let mut children = Children::take(n);
+
+        let is_in_child = std::mem::replace(&mut self.is_in_child, true);
+        n.visit_mut_children_with(self);
+        let mut exprs = children.exprs_mut();
+        let names: Vec<Option<String>> = (exprs.iter())
+            .map(|child| element_expr_name(child, &self.element_factories))
+            .collect();
+        let root = self.transparent_root(&names);
+        for (i, child) in exprs.iter_mut().enumerate() {
+            self.is_in_child = root != Some(i);
+            child.visit_mut_with(self);
         }
+        self.is_in_child = is_in_child;
 
-        if let JSXElementName::Ident(ident) = &element_name {
-            self.parent_id = ident.to_id();
-        } else {
-            panic!("parent_name is not type Ident");
+        children.restore(n);
+    }
+
+    /**
+     * Index of the child which a transparent element passes the attribute on to:
+     * the first host element (or transparent element, which passes it on again),
+     * else the first element of any kind
+     * <Suspense><Spinner /><main /></Suspense>  -> 1
+     * <Suspense><Content /></Suspense>          -> 0
+     */
+    fn transparent_root(&self, names: &[Option<String>]) -> Option<usize> {
+        let is_host = |name: &str| {
+            self.preset.is_host_element(name)
+                || is_transparent_element(&self.transparent_elements, name)
+        };
+        (names.iter())
+            .position(|name| name.as_deref().is_some_and(is_host))
+            .or_else(|| names.iter().position(Option::is_some))
+    }
+
+    /**
+     * Visit a root which is one of transparentElements (<Fragment>, <ThemeContext.Provider>, ...):
+     * a child element is the root instead
+     */
+    fn visit_mut_transparent_root(&mut self, n: &mut JSXElement) {
+        self.visit_mut_render_props(&mut n.opening.attrs);
+        self.visit_mut_transparent_children(&mut n.children);
+    }
+
+    /**
+     * Visit the children of a transparent root, one of which is the root
+     */
+    fn visit_mut_transparent_children(&mut self, children: &mut [JSXElementChild]) {
+        let names: Vec<Option<String>> = (children.iter())
+            .map(|child| match child {
+                JSXElementChild::JSXElement(element) => {
+                    Some(jsx_element_name(&element.opening.name))
+                }
+                _ => None,
+            })
+            .collect();
+        let root = self.transparent_root(&names);
+        for (i, child) in children.iter_mut().enumerate() {
+            let is_in_child = std::mem::replace(&mut self.is_in_child, root != Some(i));
+            child.visit_mut_with(self);
+            self.is_in_child = is_in_child;
         }
     }
 
-    // visit jsx closing_element
-    fn visit_mut_jsx_closing_element(&mut self, n: &mut JSXClosingElement) {
-        let element_name = &mut n.name;
-        // find parent closing_element
-        if let JSXElementName::Ident(ident) = &*element_name {
-            if ident.to_id() == self.parent_id {
-                self.is_in_child = false;
-            }
+    /**
+     * Name the component for React DevTools if displayName is enabled:
+     * UserCard.displayName = "UserCard"
+     */
+    fn add_display_name(&mut self, name: &Ident, returns_jsx: bool, has_hook: bool) {
+        if self.display_name
+            && self.mode == Mode::Transform
+            && self.is_component(name, returns_jsx, has_hook)
+        {
+            self.assignments
+                .push(Assignment::display_name(name.clone()));
         }
     }
 
-    // fn visit_mut_jsx_element_children(&mut self, n: &mut Vec<JSXElementChild>) {
-    //     // in case component has children
-    //     if n.len() > 0 {
-    //         panic!("===visit_mut_jsx_element_children==={:?}", n);
-    //     }
-    // }
-}
+    /**
+     * Add the attribute to a styled primitive following styled:
+     * const Wrapper = styled.div`...`
+     */
+    fn add_styled_id(&mut self, name: &Ident, init: &mut Expr) {
+        if self.styled == Styled::Off
+            || (self.component_detection.pascal_case && !is_pascal_case(&name.sym))
+            || vec_contains_string(self.ignore_components.clone(), name.sym.to_string())
+        {
+            return;
+        }
+        let Some(tag) = styled_tag(init) else {
+            return;
+        };
+        self.lock_component(&name.sym);
+        if self.mode != Mode::Transform {
+            return;
+        }
 
-#[plugin_transform]
-pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
-    let filename = match metadata.get_context(&TransformPluginMetadataContextKind::Filename) {
-        Some(s) => FileName::Real(s.into()),
-        None => FileName::Anon,
-    };
-    let plugin_config: Value = serde_json::from_str(
-        &metadata
-            .get_transform_plugin_config()
-            .expect("failed to get plugin config for this swc plugin"),
-    )
-    .expect("Should provide config for this swc plugin");
-    let attr_name = plugin_config["attrName"]
-        .as_str()
-        .expect("attr_name is expected")
-        .to_string();
-
-    let ignore_files = plugin_config["ignoreFiles"]
-        .as_array()
-        .expect("ignoreFiles is expected")
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<_>>();
+        let value = self.component_id(&name.sym);
+        let value = self.attr_value(&self.attr_name.clone(), value);
+        match self.styled {
+            Styled::Attrs => add_attrs(tag, &self.attr_name, value),
+            Styled::DefaultProps => {
+                let assignment = default_props(name.clone(), &self.attr_name, value);
+                self.assignments.push(assignment);
+            }
+            _ => (),
+        }
+    }
 
-    let ignore_components = plugin_config["ignoreComponents"]
-        .as_array()
-        .expect("ignoreComponent is expected")
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<_>>();
+    /**
+     * Visit the statements and insert the assignments after their declarations,
+     * unless the statements already assign them
+     */
+    fn visit_mut_stmt_list<T: StmtLike + VisitMutWith<Self>>(&mut self, stmts: &mut Vec<T>) {
+        let assignments = std::mem::take(&mut self.assignments);
+        let mut inserts = vec![];
+        for (index, stmt) in stmts.iter_mut().enumerate() {
+            stmt.visit_mut_with(self);
+            inserts.extend(self.assignments.drain(..).map(|a| (index + 1, a)));
+        }
+        self.assignments = assignments;
+
+        for (index, assignment) in inserts.into_iter().rev() {
+            let is_assigned = stmts
+                .iter()
+                .filter_map(StmtLike::as_stmt)
+                .any(|stmt| assignment.is_assigned_by(stmt));
+            if !is_assigned {
+                stmts.insert(index, T::from_stmt(assignment.into_stmt()));
+            }
+        }
+    }
 
-    let config = Config {
-        attr_name,
-        ignore_components,
-    };
+    /**
+     * "src/UserCard.tsx:12:5" for `sourceLocation`,
+     * None without the source map or for generated code
+     */
+    fn source_location_of(&self, span: Span) -> Option<String> {
+        if span.is_dummy() {
+            return None;
+        }
+        let loc = self.source_map.as_ref()?.lookup_char_pos(span.lo);
+        Some(format!("{}:{}:{}", self.filename, loc.line, loc.col.0 + 1))
+    }
 
-    let mut is_ignore = false;
-    for ignore_file in ignore_files.clone().iter_mut() {
-        let igf = ignore_file.trim_matches('\"');
-        if filename.to_string().contains(igf) {
-            is_ignore = true;
+    /**
+     * Expression of the attribute value following valueMode
+     * "user-card" or testId("user-card")
+     */
+    fn attr_value(&mut self, name: &str, value: String) -> Expr {
+        if self.value_mode == ValueMode::Runtime && name == self.attr_name {
+            self.import_runtime(RUNTIME_HELPER);
+            return helper_call(&self.runtime_names.helper, &value);
         }
+        Expr::Lit(Lit::Str(str_lit(&value)))
     }
 
-    let mut visitor = TransformVisitor::new();
-    visitor.set_config(&config, filename);
-    if is_ignore {
-        program
-    } else {
-        program.fold_with(&mut as_folder(visitor))
+    /**
+     * Record the id of the component for the lockfile
+     */
+    fn lock_component(&mut self, name: &str) {
+        if vec_contains_string(self.ignore_components.clone(), name.to_string()) {
+            return;
+        }
+        let id = self.component_id(name);
+        self.component_ids.insert(name.to_string(), id);
     }
-}
 
-fn make_test_visitor() -> TransformVisitor {
-    let mut visitor = TransformVisitor::new();
-    let config = Config {
-        attr_name: "data-testid".to_string(),
-        ignore_components: [].to_vec(),
-    };
-    visitor.set_config(&config, FileName::Anon);
-    return visitor;
-}
+    fn import_runtime(&mut self, name: &'static str) {
+        if !self.runtime_imports.contains(&name) {
+            self.runtime_imports.push(name);
+        }
+    }
 
-// https://github.com/swc-project/swc/blob/main/crates/swc/tests/simple.rs
-test!(
-    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
-        tsx: true,
-        ..Default::default()
-    }),
-    |_| as_folder(make_test_visitor()),
-    replace_jsx_attr_bool,
-    // Input codes
-    r#"
-    function ImgComponent() {
-        return
-            <img src="sample.png" lazy-load="false" />
+    /**
+     * Id of a component rendered in the current component with hierarchy "file"
+     * <SubmitButton /> in CheckoutForm -> "checkout-form__submit-button"
+     */
+    fn usage_id(&self, name: &str) -> Option<String> {
+        if self.hierarchy != Hierarchy::File
+            || self.mode != Mode::Transform
+            || self.preset.is_host_element(name)
+            || name == &*self.runtime_names.scope
+            || is_transparent_element(&self.transparent_elements, name)
+            || vec_contains_string(self.ignore_components.clone(), name.to_string())
+        {
+            return None;
+        }
+        let component_name = self.component_name();
+        if component_name.is_empty()
+            || vec_contains_string(self.ignore_components.clone(), component_name)
+        {
+            return None;
+        }
+        let child = self.preset.case_style().convert(&name.replace('.', ""));
+        Some(format!("{}__{}", self.render_id(), child))
     }
-    "#,
-    // Output codes after transformed with plugin
-    r#"
-    function ImgComponent() {
-        return
-            <img src="sample.png" lazy-load="true" data-testid="img-component" />
+
+    fn report(&mut self, span: Span, message: String) {
+        self.diagnostics.push(CheckDiagnostic {
+            span,
+            level: self.check_level,
+            message,
+        });
     }
-    "#
-);
+}
 
-test!(
-    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+impl VisitMut for TransformVisitor {
+    fn visit_mut_program(&mut self, n: &mut Program) {
+        // without locked ids there is nothing to rename
+        if !self.locked_ids.is_empty() {
+            // find the components first, to tell renamed components from new ones
+            let mut probe = self.clone();
+            probe.mode = Mode::Check;
+            probe.lockfile = None;
+            probe.locked_ids = FileIds::new();
+            n.clone().visit_mut_with(&mut probe);
+            let components: Vec<String> = probe.component_ids.into_keys().collect();
+            follow_rename(&mut self.locked_ids, &components);
+        }
+
+        n.visit_mut_children_with(self);
+
+        // scripts can't import, so they become modules
+        if let (Program::Script(script), false) = (&mut *n, self.runtime_imports.is_empty()) {
+            let mut body: Vec<ModuleItem> = script.body.drain(..).map(ModuleItem::Stmt).collect();
+            insert_runtime_import(
+                &mut body,
+                &self.runtime_imports,
+                &self.runtime_names,
+                &self.runtime_module,
+            );
+            *n = Program::Module(Module {
+                span: script.span,
+                body,
+                shebang: script.shebang.take(),
+            });
+        }
+    }
+
+    fn visit_mut_module(&mut self, n: &mut Module) {
+        if self.route_id.is_some() {
+            self.route_component = default_export_name(n);
+        }
+        if self.value_mode == ValueMode::Runtime || self.hierarchy == Hierarchy::Runtime {
+            self.runtime_names = RuntimeNames::of_module(n, Some(&self.runtime_module));
+        }
+        n.visit_mut_children_with(self);
+        if !self.runtime_imports.is_empty() {
+            insert_runtime_import(
+                &mut n.body,
+                &self.runtime_imports,
+                &self.runtime_names,
+                &self.runtime_module,
+            );
+        }
+    }
+
+    fn visit_mut_script(&mut self, n: &mut Script) {
+        if self.value_mode == ValueMode::Runtime || self.hierarchy == Hierarchy::Runtime {
+            self.runtime_names = RuntimeNames::of_script(n);
+        }
+        n.visit_mut_children_with(self);
+    }
+
+    // export default function UserCard() { ... }
+    fn visit_mut_export_default_decl(&mut self, n: &mut ExportDefaultDecl) {
+        let DefaultDecl::Fn(FnExpr {
+            ident: Some(ident),
+            function,
+        }) = &mut n.decl
+        else {
+            return n.visit_mut_children_with(self);
+        };
+        let returns_jsx = (function.body.as_ref())
+            .is_some_and(|body| parse_block_stmt(body, &self.element_factories));
+        let has_hook = self.component_detection.require_hooks && calls_hook(&function.body);
+        let ident = ident.clone();
+        self.add_display_name(&ident, returns_jsx, has_hook);
+        self.visit_mut_function(ident, returns_jsx, has_hook, n);
+    }
+
+    fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
+        if is_element_call(n, &self.element_factories) {
+            self.visit_mut_element_call(n);
+        } else {
+            n.visit_mut_children_with(self);
+        }
+    }
+
+    fn visit_mut_module_items(&mut self, n: &mut Vec<ModuleItem>) {
+        self.visit_mut_stmt_list(n);
+    }
+
+    fn visit_mut_stmts(&mut self, n: &mut Vec<Stmt>) {
+        self.visit_mut_stmt_list(n);
+    }
+
+    // TODO: CHECK ignoreComponents
+    fn visit_mut_fn_decl(&mut self, n: &mut FnDecl) {
+        let returns_jsx = (n.function.body.as_ref())
+            .is_some_and(|body| parse_block_stmt(body, &self.element_factories));
+        let has_hook = self.component_detection.require_hooks && calls_hook(&n.function.body);
+        self.add_display_name(&n.ident, returns_jsx, has_hook);
+        self.visit_mut_function(n.ident.clone(), returns_jsx, has_hook, n);
+    }
+
+    // This function is to get component_name and check variable whether jsx component or not
+    fn visit_mut_var_declarator(&mut self, n: &mut VarDeclarator) {
+        if self.styled != Styled::Off {
+            if let (Pat::Ident(ident), Some(init)) = (&n.name, n.init.as_deref_mut()) {
+                self.add_styled_id(&ident.id, init);
+            }
+        }
+
+        let require_hooks = self.component_detection.require_hooks;
+        let function = n
+            .init
+            .as_deref()
+            .and_then(|init| inspect_function(init, require_hooks, &self.element_factories));
+
+        match (&n.name, function) {
+            (Pat::Ident(ident), Some((returns_jsx, has_hook))) => {
+                // get the function name
+                let ident = ident.id.clone();
+                self.add_display_name(&ident, returns_jsx, has_hook);
+                self.visit_mut_function(ident, returns_jsx, has_hook, n)
+            }
+            // `render` of a Storybook story (or a Vue component) takes the name of the variable
+            (Pat::Ident(ident), None) if n.init.as_deref().is_some_and(is_component_object) => {
+                let object_name = self.object_name.replace(ident.id.clone());
+                n.visit_mut_children_with(self);
+                self.object_name = object_name;
+            }
+            _ => n.visit_mut_children_with(self),
+        }
+    }
+
+    // components in objects: { Header: () => <h1 />, Footer() { return <footer /> } }
+    fn visit_mut_prop(&mut self, n: &mut Prop) {
+        let require_hooks = self.component_detection.require_hooks;
+        let (key, function) = match n {
+            Prop::KeyValue(KeyValueProp { key, value }) => (
+                key,
+                inspect_function(value, require_hooks, &self.element_factories),
+            ),
+            Prop::Method(MethodProp { key, function }) => {
+                let returns_jsx = (function.body.as_ref())
+                    .is_some_and(|body| parse_block_stmt(body, &self.element_factories));
+                let has_hook = require_hooks && calls_hook(&function.body);
+                (key, Some((returns_jsx, has_hook)))
+            }
+            _ => return n.visit_mut_children_with(self),
+        };
+
+        let name = match key {
+            PropName::Ident(ident) if &*ident.sym == "render" => {
+                Some(self.object_name.clone().unwrap_or_else(|| ident.clone()))
+            }
+            PropName::Ident(ident) => Some(ident.clone()),
+            PropName::Str(s) => Some(Ident::new(s.value.clone(), s.span)),
+            // computed or numeric keys
+            _ => None,
+        };
+        match (name, function) {
+            (Some(name), Some((returns_jsx, has_hook))) => {
+                self.visit_mut_function(name, returns_jsx, has_hook, n)
+            }
+            _ => n.visit_mut_children_with(self),
+        }
+    }
+
+    // visit jsx opening_element
+    fn visit_mut_jsx_opening_element(&mut self, n: &mut JSXOpeningElement) {
+        // CHECK: support for sef-closing component
+        if self.is_in_child {
+            return;
+        }
+
+        // add "data-testid"(by default) if there is no "data-testid"(by default) attribute.
+        let mut existing = RootAttrs::default();
+        // the id of <TestIdScope id="user-card"> added by the previous run with hierarchy "runtime"
+        let is_scope = jsx_element_name(&n.name) == *self.runtime_names.scope;
+        for attr_or_spread in n.attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
+                // the attribute added by the previous run is an Ident even if attrName has a namespace,
+                // but it is re-parsed as a JSXNamespacedName
+                let name = jsx_attr_name(&attr.name);
+                if name == self.attr_name || (is_scope && name == "id") {
+                    existing.has_attr = true;
+                    // data-testid="user-card" or data-testid={testId("user-card")}
+                    let value = match &attr.value {
+                        Some(JSXAttrValue::Lit(Lit::Str(s))) => Some(s),
+                        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                            expr: JSXExpr::Expr(expr),
+                            ..
+                        })) => helper_value(expr, &self.runtime_names.helper),
+                        _ => None,
+                    };
+                    if let Some(s) = value {
+                        existing.current_value = Some((s.span, s.value.to_string()));
+                    }
+                }
+                existing.has_location |= name == SOURCE_LOCATION_ATTR;
+            }
+        }
+
+        for (name, value) in self.visit_root(n.span, existing) {
+            if name != self.attr_name {
+                let value = self.attr_value(&name, value);
+                n.attrs.push(jsx_attr(&name, value));
+                continue;
+            }
+            match self.hierarchy {
+                Hierarchy::Off => {
+                    let value = self.attr_value(&name, value);
+                    n.attrs.push(jsx_attr(&name, value));
+                }
+                // before the spread props, so that the id passed by the parent wins
+                Hierarchy::File => {
+                    let value = self.attr_value(&name, value);
+                    n.attrs.insert(0, jsx_attr(&name, value));
+                }
+                Hierarchy::Runtime => {
+                    n.attrs.push(jsx_attr(&name, scope_id(&self.runtime_names)));
+                    self.scope = Some(value);
+                }
+            }
+        }
+
+        if self.mode == Mode::Transform {
+            for attr_or_spread in n.attrs.iter_mut() {
+                if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
+                    // almost same as visit_mut_jsx_attr(update name or value of jsx attribute) function
+                    if let JSXAttrName::Ident(name) = &mut attr.name {
+                        if let Some(JSXAttrValue::Lit(Lit::Str(s))) = &mut attr.value {
+                            if &*name.sym == "lazy-load" && &*s.value == "false" {
+                                s.span = DUMMY_SP;
+                                s.value = Atom::from("true");
+                                s.raw = Some("\"true\"".into());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // visit jsx element
+    // only the root element of a component gets the attribute
+    fn visit_mut_jsx_element(&mut self, n: &mut JSXElement) {
+        if !self.is_in_child
+            && self.mode != Mode::Strip
+            && is_transparent_element(
+                &self.transparent_elements,
+                &jsx_element_name(&n.opening.name),
+            )
+        {
+            return self.visit_mut_transparent_root(n);
+        }
+
+        // every element, whether it is a root or not
+        if self.mode == Mode::Strip {
+            let attr_name = self.attr_name.clone();
+            n.opening
+                .attrs
+                .retain(|attr_or_spread| match attr_or_spread {
+                    JSXAttrOrSpread::JSXAttr(attr) => {
+                        let name = jsx_attr_name(&attr.name);
+                        name != attr_name && name != SOURCE_LOCATION_ATTR
+                    }
+                    JSXAttrOrSpread::SpreadElement(_) => true,
+                });
+            return n.visit_mut_children_with(self);
+        }
+
+        if !self.is_in_child {
+            // the root element
+            n.opening.visit_mut_with(self);
+        } else if let Some(id) = self.usage_id(&jsx_element_name(&n.opening.name)) {
+            let has_attr = n.opening.attrs.iter().any(|attr_or_spread| {
+                matches!(attr_or_spread, JSXAttrOrSpread::JSXAttr(attr) if jsx_attr_name(&attr.name) == self.attr_name)
+            });
+            if !has_attr {
+                let value = self.attr_value(&self.attr_name.clone(), id);
+                n.opening.attrs.push(jsx_attr(&self.attr_name, value));
+            }
+        }
+        let scope = self.scope.take();
+        self.visit_mut_render_props(&mut n.opening.attrs);
+
+        // elements nested in the root are children, whatever their names are
+        let is_in_child = std::mem::replace(&mut self.is_in_child, true);
+        n.children.visit_mut_with(self);
+        self.is_in_child = is_in_child;
+
+        if let Some(id) = scope {
+            self.import_runtime(SCOPE_COMPONENT);
+            wrap_in_scope(n, &id, &self.runtime_names);
+        }
+    }
+
+    // <>...</> is a Fragment as well
+    fn visit_mut_jsx_fragment(&mut self, n: &mut JSXFragment) {
+        if !self.is_in_child && self.mode != Mode::Strip {
+            return self.visit_mut_transparent_children(&mut n.children);
+        }
+        n.visit_mut_children_with(self);
+    }
+
+    // render prop in children: <Formik>{(form) => <form />}</Formik>
+    fn visit_mut_jsx_expr_container(&mut self, n: &mut JSXExprContainer) {
+        if let JSXExpr::Expr(expr) = &mut n.expr {
+            if is_render_function(expr, &self.element_factories) {
+                if let Some(scope) = self.render_scope("children") {
+                    self.visit_mut_scope(Some(scope), expr);
+                    return;
+                }
+            }
+        }
+        n.visit_mut_children_with(self);
+    }
+}
+
+#[plugin_transform]
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let filename = match metadata.get_context(&TransformPluginMetadataContextKind::Filename) {
+        Some(s) => FileName::Real(s.into()),
+        None => FileName::Anon,
+    };
+    let plugin_config = match metadata.get_transform_plugin_config() {
+        Some(json) => serde_json::from_str::<Value>(&json)
+            .map_err(|err| format!("invalid plugin config: {}", err)),
+        None => Err("invalid plugin config: the plugin has no config, \
+                     it needs at least { \"ignoreFiles\": [], \"ignoreComponents\": [] }"
+            .to_string()),
+    };
+    let env = metadata.get_context(&TransformPluginMetadataContextKind::Env);
+    let config = match plugin_config
+        .and_then(|plugin_config| Config::from_value_with_env(&plugin_config, env.as_deref()))
+    {
+        Ok(config) => config,
+        Err(message) => {
+            HANDLER.with(|handler| handler.err(&message));
+            return program;
+        }
+    };
+    let cwd = metadata.get_context(&TransformPluginMetadataContextKind::Cwd);
+    let filename = config.relative_filename(&filename, cwd.as_deref());
+    if config.is_ignored_file(&filename) {
+        return program;
+    }
+
+    let mut program = program;
+    let mut visitor = TransformVisitor::new();
+    visitor.set_config(&config, filename);
+    if !config.lockfile.is_empty() {
+        let result = lockfile_path(&config.root_dir, &config.lockfile, cwd.as_deref())
+            .and_then(|path| visitor.set_lockfile(path));
+        if let Err(message) = result {
+            HANDLER.with(|handler| handler.err(&message));
+            return program;
+        }
+    }
+    visitor.set_source_map(Lrc::new(metadata.source_map));
+    program.visit_mut_with(&mut visitor);
+    if let Err(message) = visitor.write_lockfile() {
+        HANDLER.with(|handler| handler.err(&message));
+    }
+
+    let diagnostics = visitor.take_diagnostics();
+    if !diagnostics.is_empty() {
+        HANDLER.with(|handler| {
+            for diagnostic in diagnostics.iter() {
+                diagnostic.emit(handler);
+            }
+        });
+    }
+    program
+}
+
+#[cfg(test)]
+use swc_core::{common::EqIgnoreSpan, ecma::visit::as_folder};
+
+#[cfg(test)]
+fn make_test_visitor() -> TransformVisitor {
+    make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        ignore_components: [].to_vec(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+fn make_test_visitor_with_config(config: Config) -> TransformVisitor {
+    let mut visitor = TransformVisitor::new();
+    visitor.set_config(&config, FileName::Anon);
+    visitor
+}
+
+// https://github.com/swc-project/swc/blob/main/crates/swc/tests/simple.rs
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    replace_jsx_attr_bool,
+    // Input codes
+    r#"
+    function ImgComponent() {
+        return
+            <img src="sample.png" lazy-load="false" />
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function ImgComponent() {
+        return
+            <img src="sample.png" lazy-load="true" data-testid="img-component" />
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
         tsx: true,
         ..Default::default()
     }),
@@ -617,8 +1670,8 @@ test!(
         </div>
     }
 
-    // This might be strange but OK in this case.
-    function NoReturn () { <div data-testid="no-return" /> }
+    // not a component, it doesn't return JSX
+    function NoReturn () { <div /> }
     "#
 );
 
@@ -661,8 +1714,8 @@ test!(
         </div>
     }
 
-    // This might be strange but OK in this case.
-    const NoReturn = function() { <div data-testid="no-return" /> }
+    // not a component, it doesn't return JSX
+    const NoReturn = function() { <div /> }
 
     const NoJSXReturn = function() { return 0 }
     "#
@@ -717,13 +1770,13 @@ test!(
 
     const WithoutReturn = () => <div data-testid="without-return">hello</div>;
 
-    // This might be strange but OK in this case.
+    // the first element of a fragment is the root
     const WithoutReturnJSXFragment = () => <>
-        <div data-testid="without-return" />
+        <div data-testid="without-return-jsx-fragment" />
       </>;
 
-    // This might be strange but OK in this case.
-    const NoReturn = () => { <div data-testid="no-return" /> }
+    // not a component, it doesn't return JSX
+    const NoReturn = () => { <div /> }
     "#
 );
 
@@ -767,7 +1820,7 @@ test!(
         ..Default::default()
     }),
     |_| as_folder(make_test_visitor()),
-    exact_code_no_change,
+    early_return_and_fragment_root,
     // Input codes
     r#"
 export const SettingProfilePage: FC = () => {
@@ -823,11 +1876,11 @@ export const SettingProfilePage: FC = () => {
     r#"
 export const SettingProfilePage: FC = () => {
 
-  if (!user) return <LoadingPage />
+  if (!user) return <LoadingPage data-testid="setting-profile-page" />
 
   return <>
       <UserNavbar />
-      <div className="max-w-md mx-4 sm:mx-auto">
+      <div className="max-w-md mx-4 sm:mx-auto" data-testid="setting-profile-page">
         <h1 className="mt-8 text-2xl font-bold text-gray-800">Setting</h1>
         <SettingsTab />
         <div className="my-8">
@@ -870,3 +1923,1485 @@ export const SettingProfilePage: FC = () => {
 
     "#
 );
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        preset: Preset::ReactNative,
+        ..Default::default()
+    })),
+    react_native_preset,
+    // Input codes
+    r#"
+    const UserCard = () => (
+      <View>
+        <Text>name</Text>
+      </View>
+    )
+
+    function ProfileHeader() {
+      return <Text testID="current-test-id">header</Text>
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const UserCard = () => <View testID="userCard">
+        <Text>name</Text>
+      </View>;
+
+    function ProfileHeader() {
+      return <Text testID="current-test-id">header</Text>
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-qa".to_string(),
+        preset: Preset::ReactNative,
+        ..Default::default()
+    })),
+    preset_with_attr_name,
+    // Input codes
+    r#"
+    const UserCard = () => (
+      <div>name</div>
+    )
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const UserCard = () => <div data-qa="userCard">name</div>;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        id_template: "qa-{component}".to_string(),
+        ..Default::default()
+    })),
+    id_template,
+    // Input codes
+    r#"
+    function UserCard() {
+      return <div />
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function UserCard() {
+      return <div data-testid="qa-user-card" />
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        mode: Mode::Check,
+        ..Default::default()
+    })),
+    check_mode_no_change,
+    // Input codes
+    r#"
+    function UserCard() {
+      return <img src="sample.png" lazy-load="false" />
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function UserCard() {
+      return <img src="sample.png" lazy-load="false" />
+    }
+    "#
+);
+
+#[test]
+fn check_mode_diagnostics() {
+    let src = r#"
+    function Missing() {
+      return <div />
+    }
+
+    function Matched() {
+      return <div data-testid="matched" />
+    }
+
+    function Mismatched() {
+      return <div data-testid="other-name" />
+    }
+
+    function Dynamic({ id }) {
+      return <div data-testid={id} />
+    }
+    "#;
+    let mut file =
+        runner::parse_source(std::path::Path::new("input.tsx"), src.to_string()).unwrap();
+    let config = Config {
+        attr_name: "data-testid".to_string(),
+        mode: Mode::Check,
+        check_level: CheckLevel::Warning,
+        ..Default::default()
+    };
+
+    let messages = runner::transform_file(&mut file, &config)
+        .unwrap()
+        .unwrap()
+        .iter()
+        .map(|diagnostic| runner::format_diagnostic(&file, diagnostic))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "input.tsx:3:14: warning: root element of `Missing` has no data-testid (expected data-testid=\"missing\")",
+            "input.tsx:11:31: warning: data-testid=\"other-name\" of `Mismatched` does not match idTemplate (expected \"mismatched\")",
+        ]
+    );
+}
+
+// the plugin can run twice over the same code, e.g. with multiple loaders in a dev server
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| swc_core::common::chain!(
+        as_folder(make_test_visitor()),
+        as_folder(make_test_visitor())
+    ),
+    transform_twice,
+    // Input codes
+    r#"
+    function ImgComponent() {
+        return <img src="sample.png" lazy-load="false" />
+    }
+
+    function Given() {
+        return <div data-testid="given"><span /></div>
+    }
+
+    function Dynamic({ id }) {
+        return <div data-testid={id} />
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function ImgComponent() {
+        return <img src="sample.png" lazy-load="true" data-testid="img-component" />
+    }
+
+    function Given() {
+        return <div data-testid="given"><span /></div>
+    }
+
+    function Dynamic({ id }) {
+        return <div data-testid={id} />
+    }
+    "#
+);
+
+#[test]
+fn transform_printed_output_again() {
+    let src = r#"
+    function ImgComponent() {
+        return <img src="sample.png" lazy-load="false" />
+    }
+
+    const UserCard = () => (
+        <div>
+            <span test:id={`${name}-label`} />
+        </div>
+    )
+    "#;
+    let config = Config {
+        attr_name: "test:id".to_string(),
+        ..Default::default()
+    };
+    let mut file =
+        runner::parse_source(std::path::Path::new("input.tsx"), src.to_string()).unwrap();
+    runner::transform_file(&mut file, &config).unwrap();
+    let once = runner::print_program(&file);
+    assert!(once.contains(r#"test:id="user-card""#), "{}", once);
+
+    // compare the ASTs, codegen of JSX text is not stable over print and parse
+    let mut file = runner::parse_source(std::path::Path::new("output.tsx"), once).unwrap();
+    let printed = file.program.clone();
+    runner::transform_file(&mut file, &config).unwrap();
+    assert!(file.program.eq_ignore_span(&printed));
+}
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    component_scope,
+    // Input codes
+    r#"
+    function Page() {
+      const Header = () => (
+        <header />
+      )
+      return <main><Header /></main>
+    }
+
+    const element = <div />
+
+    const renderRows = (rows) => rows.map((row) => <li>{row}</li>)
+
+    render(<App />, root)
+
+    function Layout() {
+      return <div>{items.map((item) => <span />)}</div>
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function Page() {
+      const Header = () => <header data-testid="header" />;
+      return <main data-testid="page"><Header /></main>
+    }
+
+    const element = <div />
+
+    const renderRows = (rows) => rows.map((row) => <li>{row}</li>)
+
+    render(<App />, root)
+
+    function Layout() {
+      return <div data-testid="layout">{items.map((item) => <span />)}</div>
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    component_detection,
+    // Input codes
+    r#"
+    function renderRow(row) {
+      return <li>{row}</li>
+    }
+
+    function Table({ rows }) {
+      const renderCell = (cell) => {
+        return <td>{cell}</td>
+      }
+      return <table>{rows.map(renderRow)}</table>
+    }
+
+    function Empty() {
+      return null
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function renderRow(row) {
+      return <li>{row}</li>
+    }
+
+    function Table({ rows }) {
+      const renderCell = (cell) => {
+        return <td>{cell}</td>
+      }
+      return <table data-testid="table">{rows.map(renderRow)}</table>
+    }
+
+    function Empty() {
+      return null
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        component_detection: ComponentDetection {
+            require_hooks: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    })),
+    component_detection_require_hooks,
+    // Input codes
+    r#"
+    function Counter() {
+      const [count, setCount] = useState(0)
+      return <button />
+    }
+
+    const Memo = () => {
+      const value = React.useMemo(() => 1, [])
+      return <span />
+    }
+
+    function Static() {
+      const onClick = () => useThing()
+      return <div />
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function Counter() {
+      const [count, setCount] = useState(0)
+      return <button data-testid="counter" />
+    }
+
+    const Memo = () => {
+      const value = React.useMemo(() => 1, [])
+      return <span data-testid="memo" />
+    }
+
+    function Static() {
+      const onClick = () => useThing()
+      return <div />
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        render_props: RenderProps::Index,
+        ..Default::default()
+    })),
+    render_props_index,
+    // Input codes
+    r#"
+    function Routes() {
+      return (
+        <Switch>
+          <Route path="/" render={() => <Home />} />
+          <Formik>{(form) => (<form><Field render={() => <input />} /></form>)}</Formik>
+          <List renderItem={function (item) { return <li /> }} />
+          <Route render={Page} />
+        </Switch>
+      )
+    }
+
+    const element = <Route render={() => <Home />} />
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function Routes() {
+      return <Switch data-testid="routes">
+          <Route path="/" render={() => <Home data-testid="routes__render-0" />} />
+          <Formik>{(form) => <form data-testid="routes__render-1"><Field render={() => <input data-testid="routes__render-2" />} /></form>}</Formik>
+          <List renderItem={function (item) { return <li data-testid="routes__render-3" /> }} />
+          <Route render={Page} />
+        </Switch>
+    }
+
+    const element = <Route render={() => <Home />} />
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        render_props: RenderProps::AttrName,
+        ..Default::default()
+    })),
+    render_props_attr_name,
+    // Input codes
+    r#"
+    function UserList() {
+      return (
+        <Virtualized renderItem={(user) => <UserRow />}>
+          {(state) => <Spinner />}
+        </Virtualized>
+      )
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function UserList() {
+      return <Virtualized renderItem={(user) => <UserRow data-testid="user-list__render-item" />} data-testid="user-list">
+          {(state) => <Spinner data-testid="user-list__children" />}
+        </Virtualized>
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    object_member_components,
+    // Input codes
+    r#"
+    const components = {
+      Header: () => (<h1 />),
+      Footer() {
+        return <footer />
+      },
+      "SideBar": function () {
+        return <aside />
+      },
+      [key]: () => (<div />),
+      format: (value) => value.toString(),
+    }
+
+    export default { title: "Button", component: Button }
+
+    export const Primary = {
+      args: { label: "Button" },
+      render: (args) => {
+        return <Button {...args} />
+      },
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const components = {
+      Header: () => <h1 data-testid="header" />,
+      Footer() {
+        return <footer data-testid="footer" />
+      },
+      "SideBar": function () {
+        return <aside data-testid="side-bar" />
+      },
+      [key]: () => <div />,
+      format: (value) => value.toString(),
+    }
+
+    export default { title: "Button", component: Button }
+
+    export const Primary = {
+      args: { label: "Button" },
+      render: (args) => {
+        return <Button {...args} data-testid="primary" />
+      },
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    object_member_components_expression_bodies,
+    // Input codes (the examples of the README)
+    r#"
+    const components = { Header: () => <h1 />, Footer() { return <footer /> } }
+    export const Primary = { render: () => <Button /> }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const components = {
+      Header: () => <h1 data-testid="header" />,
+      Footer() { return <footer data-testid="footer" /> }
+    }
+    export const Primary = { render: () => <Button data-testid="primary" /> }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        component_detection: ComponentDetection {
+            pascal_case: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    })),
+    mdx_component_map,
+    // Input codes
+    r#"
+    export const mdxComponents = {
+      h1: (props) => (<h1 className="title" {...props} />),
+      pre: (props) => (<CodeBlock {...props} />),
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    export const mdxComponents = {
+      h1: (props) => <h1 className="title" {...props} data-testid="h-1" />,
+      pre: (props) => <CodeBlock {...props} data-testid="pre" />,
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        styled: Styled::Attrs,
+        ..Default::default()
+    })),
+    styled_attrs,
+    // Input codes
+    r#"
+    const Wrapper = styled.div`
+      padding: 8px;
+    `;
+    export const PrimaryButton = styled(Button)`
+      color: red;
+    `;
+    const Title = styled.h1.withConfig({ displayName: "Title" })`
+      font-size: 2em;
+    `;
+    const Input = styled.input.attrs({ type: "text" })`
+      border: 0;
+    `;
+    const Search = styled.input.withConfig({ displayName: "Search" }).attrs((props) => ({ size: props.small ? 5 : undefined }))`
+      border: 0;
+    `;
+    const Label = styled.label.attrs({ "data-testid": "field-label" })`
+      color: gray;
+    `;
+    const Box = styled.div({ display: "flex" });
+    const shadow = css`
+      box-shadow: none;
+    `;
+    const link = styled.a`
+      color: blue;
+    `;
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const Wrapper = styled.div.attrs({ "data-testid": "wrapper" })`
+      padding: 8px;
+    `;
+    export const PrimaryButton = styled(Button).attrs({ "data-testid": "primary-button" })`
+      color: red;
+    `;
+    const Title = styled.h1.withConfig({ displayName: "Title" }).attrs({ "data-testid": "title" })`
+      font-size: 2em;
+    `;
+    const Input = styled.input.attrs({ "data-testid": "input", type: "text" })`
+      border: 0;
+    `;
+    const Search = styled.input.withConfig({ displayName: "Search" }).attrs({ "data-testid": "search" }).attrs((props) => ({ size: props.small ? 5 : undefined }))`
+      border: 0;
+    `;
+    const Label = styled.label.attrs({ "data-testid": "field-label" })`
+      color: gray;
+    `;
+    const Box = styled.div.attrs({ "data-testid": "box" })({ display: "flex" });
+    const shadow = css`
+      box-shadow: none;
+    `;
+    const link = styled.a`
+      color: blue;
+    `;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        styled: Styled::DefaultProps,
+        ..Default::default()
+    })),
+    styled_default_props,
+    // Input codes
+    r#"
+    const Wrapper = styled.div`
+      padding: 8px;
+    `;
+    const Card = styled("section")`
+      margin: 0;
+    `;
+    Card.defaultProps = { "data-testid": "card" };
+
+    function List() {
+      const Item = styled.li`
+        list-style: none;
+      `;
+      return <ul />;
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const Wrapper = styled.div`
+      padding: 8px;
+    `;
+    Wrapper.defaultProps = { "data-testid": "wrapper", ...Wrapper.defaultProps };
+    const Card = styled("section")`
+      margin: 0;
+    `;
+    Card.defaultProps = { "data-testid": "card" };
+
+    function List() {
+      const Item = styled.li`
+        list-style: none;
+      `;
+      Item.defaultProps = { "data-testid": "item", ...Item.defaultProps };
+      return <ul data-testid="list" />;
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        display_name: true,
+        ..Default::default()
+    })),
+    display_name,
+    // Input codes
+    r#"
+    export const UserCard = memo(({ user }) => (<div>{user.name}</div>));
+    const Input = React.forwardRef(function (props, ref) {
+      return <input ref={ref} {...props} />;
+    });
+    export function Header() {
+      return <header />;
+    }
+    const Footer = () => (<footer />);
+    Footer.displayName = "PageFooter";
+    const renderRow = () => (<tr />);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    export const UserCard = memo(({ user }) => <div data-testid="user-card">{user.name}</div>);
+    UserCard.displayName = "UserCard";
+    const Input = React.forwardRef(function (props, ref) {
+      return <input ref={ref} {...props} data-testid="input" />;
+    });
+    Input.displayName = "Input";
+    export function Header() {
+      return <header data-testid="header" />;
+    }
+    Header.displayName = "Header";
+    const Footer = () => <footer data-testid="footer" />;
+    Footer.displayName = "PageFooter";
+    const renderRow = () => <tr />;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        display_name: true,
+        ..Default::default()
+    })),
+    display_name_of_wrapped_arrow,
+    // Input codes (the example of the README)
+    r#"
+    const UserCard = memo(() => <div />)
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const UserCard = memo(() => <div data-testid="user-card" />);
+    UserCard.displayName = "UserCard";
+    "#
+);
+
+#[test]
+fn config_profiles() {
+    let plugin_config = serde_json::json!({
+        "attrName": "data-testid",
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "displayName": true,
+        "profiles": {
+            "development": { "sourceLocation": true },
+            "production": { "mode": "strip" },
+        },
+    });
+
+    let development = Config::from_value_with_env(&plugin_config, Some("development")).unwrap();
+    assert_eq!(development.mode, Mode::Transform);
+    assert!(development.source_location);
+    assert!(development.display_name);
+
+    let production = Config::from_value_with_env(&plugin_config, Some("production")).unwrap();
+    assert_eq!(production.mode, Mode::Strip);
+    assert!(!production.source_location);
+    assert!(!production.display_name);
+
+    // no profile for the env
+    let test = Config::from_value_with_env(&plugin_config, Some("test")).unwrap();
+    assert_eq!(test.mode, Mode::Transform);
+    assert!(!test.source_location);
+    assert_eq!(test.attr_name, "data-testid");
+}
+
+#[test]
+fn dom_framework_presets() {
+    let src = r#"
+    function UserList() {
+        return <ul><Show when={open}><Avatar /></Show><Transition><Badge /></Transition></ul>;
+    }
+    "#;
+    let transform = |preset: &str| {
+        let config = Config::from_value(&serde_json::json!({
+            "preset": preset,
+            "hierarchy": "file",
+            "ignoreFiles": [],
+            "ignoreComponents": [],
+        }))
+        .unwrap();
+        let mut file =
+            runner::parse_source(std::path::Path::new("input.tsx"), src.to_string()).unwrap();
+        runner::transform_file(&mut file, &config).unwrap();
+        runner::print_program(&file)
+    };
+
+    for (preset, show, transition) in [
+        ("preact", true, true),
+        ("solid", false, true),
+        ("vue", true, false),
+    ] {
+        let output = transform(preset);
+        assert!(
+            output.contains(r#"<ul data-testid="user-list">"#),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains(r#"<Avatar data-testid="user-list__avatar"/>"#),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains(r#"<Badge data-testid="user-list__badge"/>"#),
+            "{}",
+            output
+        );
+        // built-in components render no element to put the id on
+        assert_eq!(output.contains("user-list__show"), show, "{}", output);
+        assert_eq!(
+            output.contains("user-list__transition"),
+            transition,
+            "{}",
+            output
+        );
+    }
+}
+
+#[test]
+fn config_errors() {
+    let required = serde_json::json!({ "ignoreFiles": [], "ignoreComponents": [] });
+    assert!(Config::from_value(&required).is_ok());
+    let missing = Config::from_value(&serde_json::json!({ "ignoreFiles": [] }));
+    assert!(
+        missing
+            .as_ref()
+            .is_err_and(|err| err.contains("missing field `ignoreComponents`")),
+        "{:?}",
+        missing
+    );
+
+    let misspelled = Config::from_value(&serde_json::json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "idTemplte": "qa-{component}",
+    }));
+    assert!(
+        misspelled
+            .as_ref()
+            .is_err_and(|err| err.contains("unknown field `idTemplte`")),
+        "{:?}",
+        misspelled
+    );
+
+    let invalid = Config::from_value(&serde_json::json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "mode": "remove",
+    }));
+    assert!(
+        invalid
+            .as_ref()
+            .is_err_and(|err| err.contains("unknown variant `remove`")),
+        "{:?}",
+        invalid
+    );
+
+    let runtime = Config::from_value(&serde_json::json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "valueMode": "runtime",
+    }));
+    assert!(
+        runtime
+            .as_ref()
+            .is_err_and(|err| err.contains("runtimeModule")),
+        "{:?}",
+        runtime
+    );
+
+    let profiles = serde_json::json!({
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+        "profiles": { "production": { "mdoe": "strip" } },
+    });
+    assert!(Config::from_value_with_env(&profiles, Some("production")).is_err());
+    assert!(Config::from_value_with_env(&profiles, Some("development")).is_ok());
+}
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        mode: Mode::Strip,
+        ..Default::default()
+    })),
+    strip_mode,
+    // Input codes
+    r#"
+    const UserCard = () => (
+        <div data-testid="user-card" data-source-location="src/UserCard.tsx:2:9">
+            <img lazy-load="false" data-testid="avatar" {...props} />
+        </div>
+    );
+    const Header = () => React.createElement("header", { "data-testid": "header", id: "top" });
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const UserCard = () => <div>
+            <img lazy-load="false" {...props} />
+        </div>;
+    const Header = () => React.createElement("header", { id: "top" });
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |t| {
+        let mut visitor = TransformVisitor::new();
+        visitor.set_config(
+            &Config {
+                attr_name: "data-testid".to_string(),
+                source_location: true,
+                ..Default::default()
+            },
+            FileName::Real("src/UserCard.tsx".into()),
+        );
+        visitor.set_source_map(t.cm.clone());
+        as_folder(visitor)
+    },
+    source_location,
+    // Input codes
+    r#"
+const UserCard = () => (
+  <div>
+    <Avatar />
+  </div>
+);
+function Header() {
+  return <header data-source-location="src/Header.tsx:1:1" />;
+}
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+const UserCard = () => <div data-testid="user-card" data-source-location="src/UserCard.tsx:3:3">
+    <Avatar />
+  </div>;
+function Header() {
+  return <header data-source-location="src/Header.tsx:1:1" data-testid="header" />;
+}
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    transparent_roots,
+    // Input codes
+    r#"
+    function List() {
+        return (
+            <Fragment>
+                <h2>Items</h2>
+                <ul />
+            </Fragment>
+        );
+    }
+    const App = () => (
+        <ThemeContext.Provider value={theme}>
+            <React.Fragment key="app">
+                {title}
+                <main />
+            </React.Fragment>
+        </ThemeContext.Provider>
+    );
+    const Empty = () => (<React.Fragment>{children}</React.Fragment>);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function List() {
+        return <Fragment>
+                <h2 data-testid="list">Items</h2>
+                <ul />
+            </Fragment>;
+    }
+    const App = () => <ThemeContext.Provider value={theme}>
+            <React.Fragment key="app">
+                {title}
+                <main data-testid="app" />
+            </React.Fragment>
+        </ThemeContext.Provider>;
+    const Empty = () => <React.Fragment>{children}</React.Fragment>;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        transparent_elements: vec!["Suspense".to_string(), "*.Provider".to_string()],
+        ..Default::default()
+    })),
+    transparent_elements_config,
+    // Input codes
+    r#"
+    const Page = () => (<Suspense fallback={<Spinner />}><Content /></Suspense>);
+    const Root = () => (<Fragment><Content /></Fragment>);
+    const Layout = () => (<Suspense><Header /><main /></Suspense>);
+    const Nav = () => React.createElement(Fragment, null, React.createElement(Logo), React.createElement("nav"));
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const Page = () => <Suspense fallback={<Spinner />}><Content data-testid="page" /></Suspense>;
+    const Root = () => <Fragment><Content data-testid="root" /></Fragment>;
+    const Layout = () => <Suspense><Header /><main data-testid="layout" /></Suspense>;
+    const Nav = () => React.createElement(Fragment, null, React.createElement(Logo), React.createElement("nav", {
+        "data-testid": "nav"
+    }));
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    create_element,
+    // Input codes
+    r#"
+    function UserCard({ user }) {
+        return React.createElement("div", { className: "card" },
+            React.createElement(Avatar, null),
+            user.name);
+    }
+    const Header = () => createElement("header", null);
+    const Footer = () => React.createElement("footer");
+    const Title = (props) => React.createElement("h1", props);
+    const Named = () => React.createElement("div", { "data-testid": "custom" });
+    const List = () => React.createElement(React.Fragment, null,
+        "Items",
+        React.createElement("ul", null, React.createElement("li", null)),
+        React.createElement("p", null));
+    function mount() {
+        return document.createElement("div");
+    }
+    function Preview({ frame }) {
+        const canvas = ownerDocument(frame).createElement("canvas");
+        const body = frame.contentDocument.createElement("body");
+        const span = doc.createElement("span");
+        return React.createElement("div", null);
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function UserCard({ user }) {
+        return React.createElement("div", { className: "card", "data-testid": "user-card" },
+            React.createElement(Avatar, null),
+            user.name);
+    }
+    const Header = () => createElement("header", { "data-testid": "header" });
+    const Footer = () => React.createElement("footer", { "data-testid": "footer" });
+    const Title = (props) => React.createElement("h1", { "data-testid": "title", ...props });
+    const Named = () => React.createElement("div", { "data-testid": "custom" });
+    const List = () => React.createElement(React.Fragment, null,
+        "Items",
+        React.createElement("ul", { "data-testid": "list" }, React.createElement("li", null)),
+        React.createElement("p", null));
+    function mount() {
+        return document.createElement("div");
+    }
+    function Preview({ frame }) {
+        const canvas = ownerDocument(frame).createElement("canvas");
+        const body = frame.contentDocument.createElement("body");
+        const span = doc.createElement("span");
+        return React.createElement("div", { "data-testid": "preview" });
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor()),
+    jsx_runtime,
+    // Input codes
+    r#"
+    import { jsx as _jsx, jsxs as _jsxs, Fragment as _Fragment } from "react/jsx-runtime";
+    export function UserCard({ user }) {
+        return /*#__PURE__*/ _jsxs("div", {
+            className: "card",
+            children: [/*#__PURE__*/ _jsx(Avatar, {}), user.name]
+        });
+    }
+    export const Page = () => /*#__PURE__*/ _jsxs(_Fragment, {
+        children: [user.name, /*#__PURE__*/ _jsx("main", { children: /*#__PURE__*/ _jsx("h1", {}) })]
+    });
+    const Layout = () => (0, _jsxRuntime.jsx)("section", {});
+    const renderRow = () => _jsx("tr", {});
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    import { jsx as _jsx, jsxs as _jsxs, Fragment as _Fragment } from "react/jsx-runtime";
+    export function UserCard({ user }) {
+        return /*#__PURE__*/ _jsxs("div", {
+            className: "card",
+            children: [/*#__PURE__*/ _jsx(Avatar, {}), user.name],
+            "data-testid": "user-card"
+        });
+    }
+    export const Page = () => /*#__PURE__*/ _jsxs(_Fragment, {
+        children: [user.name, /*#__PURE__*/ _jsx("main", { children: /*#__PURE__*/ _jsx("h1", {}), "data-testid": "page" })]
+    });
+    const Layout = () => (0, _jsxRuntime.jsx)("section", { "data-testid": "layout" });
+    const renderRow = () => _jsx("tr", {});
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        element_factories: vec![
+            "h".to_string(),
+            "createVNode".to_string(),
+            "Vue.h".to_string(),
+        ],
+        ..Default::default()
+    })),
+    hyperscript,
+    // Input codes
+    r#"
+    export function UserCard({ user }) {
+        return h("div", { class: "card" }, h(Avatar, null), user.name);
+    }
+    const Header = () => h("header", [h("h1", "Title")]);
+    const Footer = () => createVNode("footer", null, "(c)");
+    export const Page = defineComponent({
+        props: { title: String },
+        render() {
+            return h(Fragment, null, [h("main", { id: "page" })]);
+        },
+    });
+    const el = h("span");
+    export function Checksum({ data }) {
+        const hash = crypto.h("sha", data);
+        return h("code", null, hash);
+    }
+    const Nav = () => Vue.h("nav");
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    export function UserCard({ user }) {
+        return h("div", { class: "card", "data-testid": "user-card" }, h(Avatar, null), user.name);
+    }
+    const Header = () => h("header", { "data-testid": "header" }, [h("h1", "Title")]);
+    const Footer = () => createVNode("footer", { "data-testid": "footer" }, "(c)");
+    export const Page = defineComponent({
+        props: { title: String },
+        render() {
+            return h(Fragment, null, [h("main", { id: "page", "data-testid": "page" })]);
+        },
+    });
+    const el = h("span");
+    export function Checksum({ data }) {
+        const hash = crypto.h("sha", data);
+        return h("code", { "data-testid": "checksum" }, hash);
+    }
+    const Nav = () => Vue.h("nav", { "data-testid": "nav" });
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| {
+        let mut visitor = TransformVisitor::new();
+        visitor.set_config(
+            &Config {
+                attr_name: "data-testid".to_string(),
+                route_ids: Some(RouteIds::default()),
+                ..Default::default()
+            },
+            FileName::Real("/repo/src/app/users/[id]/page.tsx".into()),
+        );
+        as_folder(visitor)
+    },
+    route_ids_app_router,
+    // Input codes
+    r#"
+    function UserCard() {
+        return <div />;
+    }
+    export default function Page() {
+        return <main><UserCard /></main>;
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function UserCard() {
+        return <div data-testid="user-card" />;
+    }
+    export default function Page() {
+        return <main data-testid="page-users-id"><UserCard /></main>;
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| {
+        let mut visitor = TransformVisitor::new();
+        visitor.set_config(
+            &Config {
+                attr_name: "data-testid".to_string(),
+                route_ids: Some(RouteIds::default()),
+                ..Default::default()
+            },
+            FileName::Real("pages/users/index.tsx".into()),
+        );
+        as_folder(visitor)
+    },
+    route_ids_pages_router,
+    // Input codes
+    r#"
+    const UsersPage = () => (<main />);
+    export default UsersPage;
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    const UsersPage = () => <main data-testid="page-users" />;
+    export default UsersPage;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        value_mode: ValueMode::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    })),
+    runtime_values,
+    // Input codes
+    r#"
+    "use client";
+    import { useState } from "react";
+    export function UserCard() {
+        return <div><Avatar /></div>;
+    }
+    const Header = () => React.createElement("header", null);
+    const Footer = () => (<footer data-testid={testId("footer")} />);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    "use client";
+    import { testId as _testId } from "@/lib/test-id";
+    import { useState } from "react";
+    export function UserCard() {
+        return <div data-testid={_testId("user-card")}><Avatar /></div>;
+    }
+    const Header = () => React.createElement("header", { "data-testid": _testId("header") });
+    const Footer = () => <footer data-testid={testId("footer")} />;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        value_mode: ValueMode::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    })),
+    runtime_values_imported,
+    // Input codes
+    r#"
+    import { testId } from "@/lib/test-id";
+    const Header = () => (<header data-testid={testId("header")} />);
+    const Footer = () => (<footer />);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    import { testId } from "@/lib/test-id";
+    const Header = () => <header data-testid={testId("header")} />;
+    const Footer = () => <footer data-testid={testId("footer")} />;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        hierarchy: Hierarchy::File,
+        ..Default::default()
+    })),
+    hierarchy_file,
+    // Input codes
+    r#"
+    function SubmitButton(props) {
+        return <button {...props} />;
+    }
+    export function CheckoutForm() {
+        return (
+            <Form>
+                <Form.Item><input /></Form.Item>
+                <SubmitButton />
+                <SubmitButton data-testid="pay" />
+                <Fragment><p /></Fragment>
+                {React.createElement(Avatar, null)}
+            </Form>
+        );
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    function SubmitButton(props) {
+        return <button data-testid="submit-button" {...props} />;
+    }
+    export function CheckoutForm() {
+        return <Form data-testid="checkout-form">
+                <Form.Item data-testid="checkout-form__form-item"><input /></Form.Item>
+                <SubmitButton data-testid="checkout-form__submit-button" />
+                <SubmitButton data-testid="pay" />
+                <Fragment><p /></Fragment>
+                {React.createElement(Avatar, { "data-testid": "checkout-form__avatar" })}
+            </Form>;
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        preset: Preset::ReactNative,
+        hierarchy: Hierarchy::File,
+        ..Default::default()
+    })),
+    hierarchy_file_react_native,
+    // Input codes
+    r#"
+    export function ProfileScreen() {
+        return (
+            <SafeAreaView>
+                <Animated.View><Text>Profile</Text></Animated.View>
+                <Avatar />
+            </SafeAreaView>
+        );
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    export function ProfileScreen() {
+        return <SafeAreaView testID="profileScreen">
+                <Animated.View><Text>Profile</Text></Animated.View>
+                <Avatar testID="profileScreen__avatar" />
+            </SafeAreaView>;
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        hierarchy: Hierarchy::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    })),
+    hierarchy_runtime,
+    // Input codes
+    r#"
+    import { TestIdScope } from "@/lib/test-id";
+    export function CheckoutForm() {
+        return (
+            <form>
+                <SubmitButton />
+            </form>
+        );
+    }
+    const Header = () => (<TestIdScope id="header">{(id) => <header data-testid={id} />}</TestIdScope>);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    import { TestIdScope } from "@/lib/test-id";
+    export function CheckoutForm() {
+        return <TestIdScope id="checkout-form">{(_testIdScope) => <form data-testid={_testIdScope}>
+                <SubmitButton />
+            </form>}</TestIdScope>;
+    }
+    const Header = () => <TestIdScope id="header">{(id) => <header data-testid={id} />}</TestIdScope>;
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        hierarchy: Hierarchy::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    })),
+    hierarchy_runtime_id_prop,
+    // Input codes
+    r#"
+    function UserRow({ id, name }) {
+        return <li onClick={() => select(id)}>{name} #{id}</li>;
+    }
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    import { TestIdScope as _TestIdScope } from "@/lib/test-id";
+    function UserRow({ id, name }) {
+        return <_TestIdScope id="user-row">{(_testIdScope) => <li onClick={() => select(id)} data-testid={_testIdScope}>{name} #{id}</li>}</_TestIdScope>;
+    }
+    "#
+);
+
+test!(
+    swc_core::ecma::parser::Syntax::Typescript(swc_core::ecma::parser::TsConfig {
+        tsx: true,
+        ..Default::default()
+    }),
+    |_| as_folder(make_test_visitor_with_config(Config {
+        attr_name: "data-testid".to_string(),
+        value_mode: ValueMode::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    })),
+    runtime_values_local_names,
+    // Input codes
+    r#"
+    const testId = 1;
+    const _testId = 2;
+    const Header = () => (<header />);
+    "#,
+    // Output codes after transformed with plugin
+    r#"
+    import { testId as _testId2 } from "@/lib/test-id";
+    const testId = 1;
+    const _testId = 2;
+    const Header = () => <header data-testid={_testId2("header")} />;
+    "#
+);
+
+#[test]
+fn runtime_values_in_script() {
+    let config = Config {
+        attr_name: "data-testid".to_string(),
+        value_mode: ValueMode::Runtime,
+        runtime_module: "@/lib/test-id".to_string(),
+        ..Default::default()
+    };
+    // no import or export, so it is parsed as a script
+    let src = "const Header = () => <header />;";
+    let mut file = runner::parse_source(std::path::Path::new("input.js"), src.to_string()).unwrap();
+    assert!(matches!(file.program, Program::Script(_)));
+    runner::transform_file(&mut file, &config).unwrap();
+    let output = runner::print_program(&file);
+    assert!(
+        output.starts_with(r#"import { testId as _testId } from "@/lib/test-id";"#),
+        "{}",
+        output
+    );
+}
@@ -0,0 +1,380 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::path::{project_root, relative_path, WASM_CWD};
+
+/**
+ * Ids of the components of a file by their names
+ */
+pub type FileIds = BTreeMap<String, String>;
+
+/**
+ * Ids of each file by its path relative to the project root
+ *
+ * {
+ *   "src/UserCard.tsx": { "UserCard": "user-card" }
+ * }
+ */
+type Lockfile = BTreeMap<String, FileIds>;
+
+/**
+ * Path of the lockfile, relative to rootDir.
+ * swc's wasm host only mounts the working directory (at /cwd),
+ * so a lockfile outside of it can't be reached from the plugin.
+ */
+pub fn lockfile_path(root_dir: &str, lockfile: &str, cwd: Option<&str>) -> Result<PathBuf, String> {
+    if !cfg!(target_arch = "wasm32") {
+        return Ok(Path::new(root_dir).join(lockfile));
+    }
+    mounted_path(root_dir, lockfile, cwd)
+}
+
+/**
+ * Path of the lockfile under the /cwd mount
+ * rootDir "packages/web"           -> /cwd/packages/web/testids.lock.json
+ * rootDir "/home/me/app/packages"  -> /cwd/packages/testids.lock.json (cwd /home/me/app)
+ */
+fn mounted_path(root_dir: &str, lockfile: &str, cwd: Option<&str>) -> Result<PathBuf, String> {
+    let path = match project_root(root_dir, cwd) {
+        Some(root) => Path::new(&root).join(lockfile),
+        None => PathBuf::from(lockfile),
+    };
+    let relative = relative_path(&path.to_string_lossy(), cwd);
+    if relative.starts_with('/') || relative.get(1..2) == Some(":") {
+        return Err(format!(
+            "invalid plugin config: lockfile {} is outside of the working directory, \
+             which is the only directory swc's wasm host can reach",
+            path.display()
+        ));
+    }
+    Ok(Path::new(WASM_CWD).join(relative))
+}
+
+/**
+ * The lockfile, empty if it does not exist yet
+ */
+fn read(path: &Path) -> Result<Lockfile, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|err| {
+            format!(
+                "invalid lockfile {}: {} (it should map files to the ids of their components, \
+                 resolve any merge conflict or delete it to start over)",
+                path.display(),
+                err
+            )
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Lockfile::new()),
+        Err(err) => Err(format!("failed to read {}: {}", path.display(), err)),
+    }
+}
+
+/**
+ * Ids of the file, read under the lock if other files may be written at the same time
+ */
+pub fn read_file_ids(path: &Path, filename: &str, is_written: bool) -> Result<FileIds, String> {
+    let _lock = is_written.then(|| Lock::acquire(path)).transpose()?;
+    Ok(read(path)?.remove(filename).unwrap_or_default())
+}
+
+/**
+ * Lock of the lockfile while it is read and written, since builds transform files in parallel.
+ * It is a file next to the lockfile, removed when dropped.
+ * A lock older than the timeout is left over from a build which was killed, and is taken over.
+ */
+struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    const RETRY: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn acquire(lockfile: &Path) -> Result<Self, String> {
+        let path = with_suffix(lockfile, ".lock");
+        let start = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    if Self::is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                    } else if start.elapsed() >= Self::TIMEOUT {
+                        return Err(format!(
+                            "failed to lock the lockfile: {} is held by another build \
+                             (remove it if no build is running)",
+                            path.display()
+                        ));
+                    } else if cfg!(target_arch = "wasm32") {
+                        // the WASI of swc's wasm host can't sleep
+                        std::hint::spin_loop();
+                    } else {
+                        thread::sleep(Self::RETRY);
+                    }
+                }
+                Err(err) => {
+                    return Err(format!(
+                        "failed to lock the lockfile with {}: {}",
+                        path.display(),
+                        err
+                    ))
+                }
+            }
+        }
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > Self::TIMEOUT)
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/**
+ * Replace the ids of the file, keeping the other files.
+ * The lockfile is read again under the lock right before writing, since other files may have been written since,
+ * and replaced by renaming, so that it is never read half-written.
+ * Unchanged lockfiles are not written, which would trigger file watchers.
+ */
+pub fn write_file_ids(path: &Path, filename: &str, ids: FileIds) -> Result<(), String> {
+    let _lock = Lock::acquire(path)?;
+    let mut lockfile = read(path)?;
+    if lockfile.get(filename).cloned().unwrap_or_default() == ids {
+        return Ok(());
+    }
+    if ids.is_empty() {
+        lockfile.remove(filename);
+    } else {
+        lockfile.insert(filename.to_string(), ids);
+    }
+    let json = serde_json::to_string_pretty(&lockfile).expect("ids should be serializable");
+    let temp = with_suffix(path, ".tmp");
+    fs::write(&temp, json + "\n")
+        .and_then(|_| match fs::rename(&temp, path) {
+            // the WASI of swc's wasm host doesn't rename over an existing file,
+            // so it is removed first, with the readers waiting for the lock
+            Err(_) if path.exists() => fs::remove_file(path).and_then(|_| fs::rename(&temp, path)),
+            result => result,
+        })
+        .map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+/**
+ * Carry the id of a renamed component over to its new name:
+ * the only locked component which is gone from the file and the only component which is not locked.
+ * More renames at once are ambiguous, since the lockfile doesn't keep the order of the components,
+ * so every renamed component gets a generated id, and updateLockfile drops the locked ones.
+ */
+pub fn follow_rename(locked: &mut FileIds, components: &[String]) {
+    let gone: Vec<&String> = locked
+        .keys()
+        .filter(|name| !components.contains(name))
+        .collect();
+    let new: Vec<&String> = components
+        .iter()
+        .filter(|name| !locked.contains_key(*name))
+        .collect();
+    if let ([gone], [new]) = (gone.as_slice(), new.as_slice()) {
+        let (gone, new) = ((*gone).clone(), (*new).clone());
+        if let Some(id) = locked.remove(&gone) {
+            locked.insert(new, id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runner::{parse_source, print_program, transform_file},
+        Config,
+    };
+
+    fn ids(entries: &[(&str, &str)]) -> FileIds {
+        entries
+            .iter()
+            .map(|(name, id)| (name.to_string(), id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn rename() {
+        let mut locked = ids(&[("UserCard", "user-card"), ("Header", "header")]);
+        follow_rename(
+            &mut locked,
+            &["ProfileCard".to_string(), "Header".to_string()],
+        );
+        assert_eq!(
+            locked,
+            ids(&[("ProfileCard", "user-card"), ("Header", "header")])
+        );
+    }
+
+    #[test]
+    fn ambiguous_renames() {
+        let locked = ids(&[("UserCard", "user-card"), ("Header", "header")]);
+        let mut renamed = locked.clone();
+        follow_rename(
+            &mut renamed,
+            &["ProfileCard".to_string(), "Nav".to_string()],
+        );
+        assert_eq!(renamed, locked);
+
+        // a new component next to the locked ones
+        let mut added = locked.clone();
+        follow_rename(
+            &mut added,
+            &[
+                "UserCard".to_string(),
+                "Header".to_string(),
+                "Footer".to_string(),
+            ],
+        );
+        assert_eq!(added, locked);
+    }
+
+    #[test]
+    fn wasm_mount() {
+        let cwd = Some("/home/me/app");
+        let mounted = |root_dir| mounted_path(root_dir, "testids.lock.json", cwd);
+        assert_eq!(mounted("").unwrap(), Path::new("/cwd/testids.lock.json"));
+        assert_eq!(
+            mounted("packages/web").unwrap(),
+            Path::new("/cwd/packages/web/testids.lock.json")
+        );
+        assert_eq!(
+            mounted("/home/me/app/packages/web").unwrap(),
+            Path::new("/cwd/packages/web/testids.lock.json")
+        );
+        assert!(mounted("/srv/web").is_err());
+        assert!(mounted("../shared").is_err());
+    }
+
+    #[test]
+    fn parallel_writes() {
+        let dir =
+            std::env::temp_dir().join(format!("swc-test-plugin-parallel-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("testids.lock.json");
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    let name = format!("Card{}", i);
+                    write_file_ids(
+                        &path,
+                        &format!("src/{}.tsx", name),
+                        ids(&[(&name, &format!("card-{}", i))]),
+                    )
+                    .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let lockfile: Lockfile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(lockfile.len(), 8);
+        assert!(!with_suffix(&path, ".lock").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /**
+     * Transform the source as src/Cards.tsx with the lockfile and return the output
+     */
+    fn transform(lockfile: &Path, src: &str, update: bool) -> String {
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            lockfile: lockfile.to_string_lossy().to_string(),
+            update_lockfile: update,
+            ..Default::default()
+        };
+        let mut file = parse_source(Path::new("src/Cards.tsx"), src.to_string()).unwrap();
+        transform_file(&mut file, &config).unwrap().unwrap();
+        print_program(&file)
+    }
+
+    #[test]
+    fn locked_ids() {
+        let dir =
+            std::env::temp_dir().join(format!("swc-test-plugin-lockfile-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("testids.lock.json");
+        fs::write(
+            &path,
+            r#"{ "src/Other.tsx": { "Other": "other" }, "src/Cards.tsx": { "UserCard": "user-card" } }"#,
+        )
+        .unwrap();
+
+        // UserCard was renamed to ProfileCard
+        let output = transform(&path, "const ProfileCard = () => (<div />);", false);
+        assert!(output.contains(r#"data-testid="user-card""#), "{}", output);
+
+        // the ids of the file are written, with the renamed component under its new name
+        transform(&path, "const ProfileCard = () => (<div />);", true);
+        // then new components are added
+        transform(
+            &path,
+            "const ProfileCard = () => (<div />);\nconst Footer = () => (<footer />);",
+            true,
+        );
+        let lockfile: Lockfile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(lockfile["src/Other.tsx"], ids(&[("Other", "other")]));
+        assert_eq!(
+            lockfile["src/Cards.tsx"],
+            ids(&[("Footer", "footer"), ("ProfileCard", "user-card")])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stale_lock_and_invalid_lockfile() {
+        let dir =
+            std::env::temp_dir().join(format!("swc-test-plugin-stale-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("testids.lock.json");
+
+        // left over from a killed build
+        let lock = fs::File::create(with_suffix(&path, ".lock")).unwrap();
+        lock.set_modified(std::time::SystemTime::now() - Lock::TIMEOUT * 2)
+            .unwrap();
+        let start = Instant::now();
+        write_file_ids(&path, "src/App.tsx", ids(&[("App", "app")])).unwrap();
+        assert!(start.elapsed() < Lock::TIMEOUT);
+        assert!(!with_suffix(&path, ".lock").exists());
+
+        fs::write(&path, "<<<<<<< HEAD\n{}\n=======\n{}\n>>>>>>> main\n").unwrap();
+        let err = read_file_ids(&path, "src/App.tsx", true).unwrap_err();
+        assert!(err.starts_with("invalid lockfile"), "{}", err);
+        assert!(write_file_ids(&path, "src/App.tsx", FileIds::new()).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
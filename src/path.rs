@@ -0,0 +1,138 @@
+/**
+ * Directory which swc's wasm host mounts the working directory at
+ */
+pub const WASM_CWD: &str = "/cwd";
+
+/**
+ * Segments of the path with `/` separators, with `.` and `..` resolved
+ * C:\app\.\src\..\App.tsx -> ["C:", "app", "App.tsx"]
+ */
+fn segments(path: &str) -> (bool, Vec<&str>) {
+    let is_absolute =
+        path.starts_with('/') || path.starts_with('\\') || path.get(1..2) == Some(":");
+    let mut segments: Vec<&str> = vec![];
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => (),
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    (is_absolute, segments)
+}
+
+/**
+ * Project root from rootDir and the working directory of the host
+ * ""                  -> cwd
+ * "packages/web"      -> cwd/packages/web
+ * "/home/me/app"      -> "/home/me/app"
+ */
+pub fn project_root(root_dir: &str, cwd: Option<&str>) -> Option<String> {
+    if root_dir.is_empty() {
+        return cwd.map(str::to_string);
+    }
+    if segments(root_dir).0 {
+        return Some(root_dir.to_string());
+    }
+    match cwd {
+        Some(cwd) => Some(format!("{}/{}", cwd, root_dir)),
+        None => Some(root_dir.to_string()),
+    }
+}
+
+/**
+ * Path of the file relative to the project root, with `/` separators
+ *
+ * /home/me/app/src/App.tsx (root /home/me/app)   -> "src/App.tsx"
+ * C:\app\src\App.tsx (root C:\app)               -> "src/App.tsx"
+ * /cwd/src/App.tsx (the mount of swc's wasm host) -> "src/App.tsx"
+ * ./src/../src/App.tsx                           -> "src/App.tsx"
+ * /tmp/App.tsx (outside of the root)             -> "/tmp/App.tsx"
+ */
+pub fn relative_path(path: &str, root: Option<&str>) -> String {
+    let (is_absolute, path_segments) = segments(path);
+    if !is_absolute {
+        return path_segments.join("/");
+    }
+
+    let (_, wasm_cwd) = segments(WASM_CWD);
+    let roots = root.map(segments).into_iter().map(|(_, root)| root);
+    for root in roots.chain([wasm_cwd]) {
+        if !root.is_empty() && path_segments.starts_with(&root) {
+            return path_segments[root.len()..].join("/");
+        }
+    }
+
+    let path = path_segments.join("/");
+    if path_segments
+        .first()
+        .is_some_and(|first| first.ends_with(':'))
+    {
+        path
+    } else {
+        format!("/{}", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_to_root() {
+        let root = Some("/home/me/app");
+        assert_eq!(
+            relative_path("/home/me/app/src/App.tsx", root),
+            "src/App.tsx"
+        );
+        assert_eq!(
+            relative_path("/home/me/app/./src/../pages/index.tsx", root),
+            "pages/index.tsx"
+        );
+        assert_eq!(
+            relative_path("/home/me/other/App.tsx", root),
+            "/home/me/other/App.tsx"
+        );
+        assert_eq!(
+            relative_path("/home/me/application/App.tsx", root),
+            "/home/me/application/App.tsx"
+        );
+        assert_eq!(relative_path("./src/App.tsx", root), "src/App.tsx");
+        assert_eq!(relative_path("src/App.tsx", None), "src/App.tsx");
+    }
+
+    #[test]
+    fn windows_paths() {
+        assert_eq!(
+            relative_path(r"C:\Users\me\app\src\App.tsx", Some(r"C:\Users\me\app")),
+            "src/App.tsx"
+        );
+        assert_eq!(relative_path(r"D:\App.tsx", Some(r"C:\app")), "D:/App.tsx");
+        assert_eq!(relative_path(r"src\App.tsx", None), "src/App.tsx");
+    }
+
+    #[test]
+    fn wasm_mount() {
+        assert_eq!(
+            relative_path("/cwd/src/App.tsx", Some("/home/me/app")),
+            "src/App.tsx"
+        );
+        assert_eq!(relative_path("/cwd/src/App.tsx", None), "src/App.tsx");
+    }
+
+    #[test]
+    fn root_dir() {
+        assert_eq!(project_root("", Some("/repo")).as_deref(), Some("/repo"));
+        assert_eq!(project_root("", None), None);
+        assert_eq!(
+            project_root("packages/web", Some("/repo")).as_deref(),
+            Some("/repo/packages/web")
+        );
+        assert_eq!(
+            project_root("/srv/web", Some("/repo")).as_deref(),
+            Some("/srv/web")
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use convert_case::{Case, Casing};
+use serde::Deserialize;
+
+/**
+ * Host elements of React Native, also as `Animated.View` and the like.
+ * Custom components usually forward `testID` to one of them.
+ */
+const REACT_NATIVE_HOST_ELEMENTS: [&str; 16] = [
+    "ActivityIndicator",
+    "FlatList",
+    "Image",
+    "ImageBackground",
+    "KeyboardAvoidingView",
+    "Modal",
+    "Pressable",
+    "SafeAreaView",
+    "ScrollView",
+    "SectionList",
+    "Switch",
+    "Text",
+    "TextInput",
+    "TouchableOpacity",
+    "TouchableWithoutFeedback",
+    "View",
+];
+
+/**
+ * Components built into Solid, which render their children without an element of their own
+ */
+const SOLID_BUILT_INS: [&str; 9] = [
+    "Dynamic",
+    "ErrorBoundary",
+    "For",
+    "Index",
+    "Match",
+    "Portal",
+    "Show",
+    "Suspense",
+    "Switch",
+];
+
+/**
+ * Components built into Vue, which render their children without an element of their own
+ */
+const VUE_BUILT_INS: [&str; 5] = [
+    "KeepAlive",
+    "Suspense",
+    "Teleport",
+    "Transition",
+    "TransitionGroup",
+];
+
+/**
+ * Case style of the generated attribute value
+ *
+ * UserCard -> "user-card" (Kebab)
+ * UserCard -> "userCard"  (Camel)
+ * UserCard -> "user_card" (Snake)
+ * UserCard -> "UserCard"  (Pascal)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaseStyle {
+    Kebab,
+    Camel,
+    Snake,
+    Pascal,
+}
+
+impl CaseStyle {
+    /**
+     * Convert to this case style from UpperCamelCase(component name)
+     */
+    pub fn convert(&self, s: &str) -> String {
+        match self {
+            CaseStyle::Kebab => s.to_case(Case::Kebab).to_lowercase(),
+            CaseStyle::Camel => s.to_case(Case::Camel),
+            CaseStyle::Snake => s.to_case(Case::Snake).to_lowercase(),
+            CaseStyle::Pascal => s.to_case(Case::Pascal),
+        }
+    }
+}
+
+/**
+ * Attribute conventions of each framework.
+ * Selected with `preset` in the plugin config ("react-dom" by default).
+ * Preact, Solid and Vue render to the DOM like react-dom, with their own built-in components.
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    #[default]
+    ReactDom,
+    ReactNative,
+    Preact,
+    Solid,
+    Vue,
+}
+
+impl Preset {
+    /**
+     * Attribute name used when `attrName` is not given
+     */
+    pub fn attr_name(&self) -> &'static str {
+        match self {
+            Preset::ReactNative => "testID",
+            Preset::ReactDom | Preset::Preact | Preset::Solid | Preset::Vue => "data-testid",
+        }
+    }
+
+    pub fn case_style(&self) -> CaseStyle {
+        match self {
+            Preset::ReactNative => CaseStyle::Camel,
+            Preset::ReactDom | Preset::Preact | Preset::Solid | Preset::Vue => CaseStyle::Kebab,
+        }
+    }
+
+    /**
+     * Whether the element is rendered by the platform itself
+     * (<div /> for the DOM, <View /> for React Native, <Show /> of Solid)
+     * rather than by a user-defined component.
+     * With hierarchy "file", only the other elements get ids of their own.
+     */
+    pub fn is_host_element(&self, name: &str) -> bool {
+        let is_lowercase = name.starts_with(|c: char| c.is_ascii_lowercase());
+        match self {
+            Preset::ReactNative => {
+                let name = name.strip_prefix("Animated.").unwrap_or(name);
+                is_lowercase || REACT_NATIVE_HOST_ELEMENTS.contains(&name)
+            }
+            Preset::ReactDom | Preset::Preact => is_lowercase,
+            Preset::Solid => is_lowercase || SOLID_BUILT_INS.contains(&name),
+            Preset::Vue => is_lowercase || VUE_BUILT_INS.contains(&name),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use serde::Deserialize;
+
+/**
+ * Ids of Next.js pages by their routes
+ *
+ * roots: directories of the routers, "app" (and any path ending with it) for the app router,
+ *        the others for the pages router
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RouteIds {
+    pub roots: Vec<String>,
+}
+
+impl Default for RouteIds {
+    fn default() -> Self {
+        Self {
+            roots: vec!["pages".to_string(), "app".to_string()],
+        }
+    }
+}
+
+/**
+ * Files of the app router which render a part of the route
+ */
+const APP_ROUTER_FILES: [&str; 7] = [
+    "page",
+    "layout",
+    "template",
+    "loading",
+    "error",
+    "not-found",
+    "default",
+];
+
+/**
+ * Name of the segment in the id, None if it is not a part of the URL
+ * [id]           -> "id"
+ * [...slug]      -> "slug"
+ * [[...slug]]    -> "slug"
+ * (marketing)    -> None (route group)
+ * @modal         -> None (parallel route)
+ * (..)photo      -> "photo" (intercepting route)
+ */
+fn segment_name(segment: &str) -> Option<&str> {
+    if segment.starts_with('@') {
+        return None;
+    }
+    let segment = segment
+        .trim_start_matches("(...)")
+        .trim_start_matches("(..)")
+        .trim_start_matches("(.)");
+    if segment.starts_with('(') && segment.ends_with(')') {
+        return None;
+    }
+    let name = segment
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_start_matches("...");
+    Some(name)
+}
+
+/**
+ * Id of the route which the file renders, None if it is not a route
+ *
+ * app/users/[id]/page.tsx         -> "page-users-id"
+ * app/(shop)/cart/layout.tsx      -> "layout-cart"
+ * app/page.tsx                    -> "page-index"
+ * pages/users/[...slug].tsx       -> "page-users-slug"
+ * pages/users/index.tsx           -> "page-users"
+ * pages/_app.tsx, pages/api/...   -> None
+ */
+pub fn route_id(path: &str, roots: &[String]) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // the innermost root, e.g. "src/app" in /home/app/src/app/page.tsx
+    let (root, start) = roots
+        .iter()
+        .filter_map(|root| {
+            let root_segments: Vec<&str> = root.split('/').filter(|s| !s.is_empty()).collect();
+            let start = (0..segments.len().saturating_sub(root_segments.len()))
+                .rev()
+                .find(|&i| segments[i..].starts_with(&root_segments))?;
+            let end = start + root_segments.len();
+            Some((root_segments, end))
+        })
+        .max_by_key(|(_, start)| *start)?;
+
+    let (file, dirs) = segments[start..].split_last()?;
+    let stem = file.rsplit_once('.').map_or(*file, |(stem, _)| stem);
+    // private folders (_components) and files (_app, _document)
+    if stem.starts_with('_') || dirs.iter().any(|dir| dir.starts_with('_')) {
+        return None;
+    }
+
+    let is_app_router = root.last() == Some(&"app");
+    let (kind, route_segments) = if is_app_router {
+        if !APP_ROUTER_FILES.contains(&stem) {
+            return None;
+        }
+        (stem, dirs.to_vec())
+    } else {
+        if dirs.first() == Some(&"api") {
+            return None;
+        }
+        let mut route_segments = dirs.to_vec();
+        if stem != "index" {
+            route_segments.push(stem);
+        }
+        ("page", route_segments)
+    };
+
+    let route: Vec<&str> = route_segments
+        .into_iter()
+        .filter_map(segment_name)
+        .collect();
+    if route.is_empty() {
+        return Some(format!("{}-index", kind));
+    }
+    Some(format!("{}-{}", kind, route.join("-")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(path: &str) -> Option<String> {
+        route_id(path, &RouteIds::default().roots)
+    }
+
+    #[test]
+    fn app_router() {
+        assert_eq!(
+            route("app/users/[id]/page.tsx").as_deref(),
+            Some("page-users-id")
+        );
+        assert_eq!(
+            route("/repo/src/app/page.tsx").as_deref(),
+            Some("page-index")
+        );
+        assert_eq!(
+            route("app/(shop)/cart/layout.tsx").as_deref(),
+            Some("layout-cart")
+        );
+        assert_eq!(
+            route("app/docs/[[...slug]]/not-found.tsx").as_deref(),
+            Some("not-found-docs-slug")
+        );
+        assert_eq!(
+            route("app/@modal/(..)photos/[id]/page.tsx").as_deref(),
+            Some("page-photos-id")
+        );
+        // the project directory is named "app" as well
+        assert_eq!(
+            route("/app/src/app/users/page.tsx").as_deref(),
+            Some("page-users")
+        );
+        assert_eq!(route("app/users/UserCard.tsx"), None);
+        assert_eq!(route("app/users/_components/page.tsx"), None);
+    }
+
+    #[test]
+    fn pages_router() {
+        assert_eq!(route("pages/index.tsx").as_deref(), Some("page-index"));
+        assert_eq!(
+            route("src/pages/users/index.tsx").as_deref(),
+            Some("page-users")
+        );
+        assert_eq!(
+            route("pages/users/[...slug].tsx").as_deref(),
+            Some("page-users-slug")
+        );
+        assert_eq!(route("pages/_app.tsx"), None);
+        assert_eq!(route("pages/api/users.ts"), None);
+        assert_eq!(route("src/components/UserCard.tsx"), None);
+    }
+
+    #[test]
+    fn custom_roots() {
+        let roots = vec!["src/routes".to_string()];
+        assert_eq!(
+            route_id("src/routes/settings/[tab].tsx", &roots).as_deref(),
+            Some("page-settings-tab")
+        );
+        assert_eq!(route_id("pages/index.tsx", &roots), None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use swc_core::{
+    common::{
+        comments::SingleThreadedComments, sync::Lrc, FileName, SourceFile, SourceMap, Spanned,
+    },
+    ecma::{
+        ast::Program,
+        codegen::{text_writer::JsWriter, Emitter},
+        parser::{EsConfig, Parser, StringInput, Syntax, TsConfig},
+        visit::VisitMutWith,
+    },
+};
+
+use crate::{lockfile::lockfile_path, CheckDiagnostic, CheckLevel, Config, TransformVisitor};
+
+/**
+ * Choose the parser syntax from the file extension.
+ * .ts/.tsx are parsed as TypeScript, everything else as JavaScript with JSX.
+ */
+pub fn syntax_for_path(path: &Path) -> Syntax {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts") | Some("mts") | Some("cts") => Syntax::Typescript(TsConfig {
+            tsx: false,
+            ..Default::default()
+        }),
+        Some("tsx") => Syntax::Typescript(TsConfig {
+            tsx: true,
+            ..Default::default()
+        }),
+        _ => Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+    }
+}
+
+/**
+ * Source file parsed outside of swc's plugin host
+ */
+pub struct ParsedFile {
+    pub cm: Lrc<SourceMap>,
+    pub fm: Lrc<SourceFile>,
+    pub comments: SingleThreadedComments,
+    pub program: Program,
+}
+
+impl ParsedFile {
+    pub fn filename(&self) -> FileName {
+        self.fm.name.clone()
+    }
+}
+
+/**
+ * Parse the source text as if it was read from `path`
+ */
+pub fn parse_source(path: &Path, src: String) -> Result<ParsedFile, String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Real(path.to_path_buf()), src);
+    let comments = SingleThreadedComments::default();
+
+    let mut parser = Parser::new(
+        syntax_for_path(path),
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+    // recoverable errors are reported as well, the output would not be reliable
+    let program =
+        parser
+            .parse_program()
+            .and_then(|program| match parser.take_errors().into_iter().next() {
+                Some(error) => Err(error),
+                None => Ok(program),
+            });
+    let program = program.map_err(|error| {
+        let loc = cm.lookup_char_pos(error.span().lo);
+        format!(
+            "{}:{}:{}: {}",
+            path.display(),
+            loc.line,
+            loc.col_display + 1,
+            error.kind().msg()
+        )
+    })?;
+
+    Ok(ParsedFile {
+        cm,
+        fm,
+        comments,
+        program,
+    })
+}
+
+/**
+ * Read and parse the file
+ */
+pub fn parse_file(path: &Path) -> Result<ParsedFile, String> {
+    let src =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    parse_source(path, src)
+}
+
+/**
+ * Print the program with its comments
+ */
+pub fn print_program(file: &ParsedFile) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: file.cm.clone(),
+            comments: Some(&file.comments),
+            wr: JsWriter::new(file.cm.clone(), "\n", &mut buf, None),
+        };
+        emitter
+            .emit_program(&file.program)
+            .expect("failed to write to in-memory buffer");
+    }
+
+    String::from_utf8(buf).expect("codegen should emit utf-8")
+}
+
+/**
+ * Run TransformVisitor over the parsed file in the same way as `process_transform`.
+ * Return the problems found in `mode: "check"`,
+ * or None if the file is ignored by `ignoreFiles`.
+ * Fails if the lockfile can't be read or written.
+ */
+pub fn transform_file(
+    file: &mut ParsedFile,
+    config: &Config,
+) -> Result<Option<Vec<CheckDiagnostic>>, String> {
+    let cwd = std::env::current_dir().ok();
+    let cwd = cwd.as_ref().map(|cwd| cwd.to_string_lossy());
+    let filename = config.relative_filename(&file.filename(), cwd.as_deref());
+    if config.is_ignored_file(&filename) {
+        return Ok(None);
+    }
+
+    let mut visitor = TransformVisitor::new();
+    visitor.set_config(config, filename);
+    if !config.lockfile.is_empty() {
+        visitor.set_lockfile(lockfile_path(
+            &config.root_dir,
+            &config.lockfile,
+            cwd.as_deref(),
+        )?)?;
+    }
+    visitor.set_source_map(file.cm.clone());
+    file.program.visit_mut_with(&mut visitor);
+    visitor.write_lockfile()?;
+    Ok(Some(visitor.take_diagnostics()))
+}
+
+/**
+ * path:line:column: level: message
+ */
+pub fn format_diagnostic(file: &ParsedFile, diagnostic: &CheckDiagnostic) -> String {
+    let loc = file.cm.lookup_char_pos(diagnostic.span.lo);
+    let level = match diagnostic.level {
+        CheckLevel::Error => "error",
+        CheckLevel::Warning => "warning",
+    };
+    format!(
+        "{}:{}:{}: {}: {}",
+        file.filename(),
+        loc.line,
+        loc.col_display + 1,
+        level,
+        diagnostic.message
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignored_file() {
+        let config = Config {
+            ignore_files: vec!["stories".to_string()],
+            ..Default::default()
+        };
+        let src = "const Card = () => (<div />);\n";
+        let mut file = parse_source(Path::new("src/stories/Card.tsx"), src.to_string()).unwrap();
+        assert!(transform_file(&mut file, &config).unwrap().is_none());
+        assert_eq!(print_program(&file), "const Card = ()=>(<div />);\n");
+
+        let mut file = parse_source(Path::new("src/Card.tsx"), src.to_string()).unwrap();
+        assert!(transform_file(&mut file, &config).unwrap().is_some());
+        assert!(print_program(&file).contains(r#"data-testid="card""#));
+    }
+
+    #[test]
+    fn unparsable_file() {
+        let src = "const Card = () => (\n  <div>;\n";
+        let err = parse_source(Path::new("src/Card.tsx"), src.to_string())
+            .err()
+            .unwrap();
+        assert!(err.starts_with("src/Card.tsx:2:"), "{}", err);
+
+        // recoverable errors fail as well
+        let err = parse_source(
+            Path::new("src/Card.tsx"),
+            "class Card { constructor() {} constructor() {} }".to_string(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.starts_with("src/Card.tsx:1:"), "{}", err);
+
+        let err = parse_file(Path::new("src/Missing.tsx")).err().unwrap();
+        assert!(err.starts_with("src/Missing.tsx: "), "{}", err);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{
+        ast::{
+            CallExpr, Callee, Expr, ExprOrSpread, ExprStmt, Ident, ImportDecl,
+            ImportNamedSpecifier, ImportSpecifier, Lit, Module, ModuleDecl, ModuleExportName,
+            ModuleItem, Script, Stmt, Str,
+        },
+        atoms::JsWord,
+        visit::{Visit, VisitWith},
+    },
+};
+
+use crate::{assignment::str_lit, hierarchy::SCOPE_COMPONENT};
+
+/**
+ * How the value of the attribute is written
+ *
+ * literal: data-testid="user-card"
+ * runtime: data-testid={testId("user-card")} with `import { testId } from "<runtimeModule>"`,
+ *          so that the app decides at runtime whether to render the ids, or prefixes them
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValueMode {
+    #[default]
+    Literal,
+    Runtime,
+}
+
+/**
+ * Function imported from runtimeModule
+ */
+pub const RUNTIME_HELPER: &str = "testId";
+
+/**
+ * Local names of the imports from runtimeModule and the parameter of the scopes,
+ * which don't clash with the names of the file:
+ * import { testId as _testId, TestIdScope as _TestIdScope } from "<runtimeModule>";
+ * Imports which the file already has (e.g. by the previous run) are used as they are.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeNames {
+    pub helper: JsWord,
+    pub scope: JsWord,
+    /// <_TestIdScope id="user-card">{(_testIdScope) => root}</_TestIdScope>,
+    /// which must not shadow the names used in the root, such as an `id` prop
+    pub scope_param: JsWord,
+}
+
+impl Default for RuntimeNames {
+    fn default() -> Self {
+        Self {
+            helper: "_testId".into(),
+            scope: "_TestIdScope".into(),
+            scope_param: "_testIdScope".into(),
+        }
+    }
+}
+
+impl RuntimeNames {
+    /**
+     * Names for the module, with its imports from runtimeModule (from any module if None)
+     */
+    pub fn of_module(module: &Module, runtime_module: Option<&str>) -> Self {
+        let mut collector = NameCollector::default();
+        module.visit_with(&mut collector);
+        Self::new(&collector.names, &module.body, runtime_module)
+    }
+
+    pub fn of_script(script: &Script) -> Self {
+        let mut collector = NameCollector::default();
+        script.visit_with(&mut collector);
+        Self::new(&collector.names, &[], None)
+    }
+
+    fn new(names: &HashSet<JsWord>, items: &[ModuleItem], runtime_module: Option<&str>) -> Self {
+        let defaults = Self::default();
+        let name = |imported: &str, alias: &JsWord| {
+            imported_as(items, imported, runtime_module)
+                .unwrap_or_else(|| unique_name(names, alias))
+        };
+        Self {
+            helper: name(RUNTIME_HELPER, &defaults.helper),
+            scope: name(SCOPE_COMPONENT, &defaults.scope),
+            scope_param: unique_name(names, &defaults.scope_param),
+        }
+    }
+
+    /**
+     * Local name of the import
+     */
+    pub fn local(&self, imported: &str) -> &JsWord {
+        if imported == SCOPE_COMPONENT {
+            &self.scope
+        } else {
+            &self.helper
+        }
+    }
+}
+
+/**
+ * Every identifier of the program, bindings or not
+ */
+#[derive(Default)]
+struct NameCollector {
+    names: HashSet<JsWord>,
+}
+
+impl Visit for NameCollector {
+    fn visit_ident(&mut self, n: &Ident) {
+        self.names.insert(n.sym.clone());
+    }
+}
+
+/**
+ * The name, or the name followed by the first number which is not used
+ * _testId, _testId2, _testId3, ...
+ */
+fn unique_name(names: &HashSet<JsWord>, name: &JsWord) -> JsWord {
+    if !names.contains(name) {
+        return name.clone();
+    }
+    (2..)
+        .map(|i| JsWord::from(format!("{}{}", name, i)))
+        .find(|name| !names.contains(name))
+        .expect("some number should be free")
+}
+
+/**
+ * _testId("user-card")
+ */
+pub fn helper_call(helper: &JsWord, value: &str) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Ident(Ident::new(helper.clone(), DUMMY_SP)))),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Lit(Lit::Str(str_lit(value)))),
+        }],
+        type_args: None,
+    })
+}
+
+/**
+ * The id passed to the helper, "user-card" of _testId("user-card")
+ */
+pub fn helper_value<'a>(expr: &'a Expr, helper: &str) -> Option<&'a Str> {
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        args,
+        ..
+    }) = expr
+    else {
+        return None;
+    };
+    if !matches!(&**callee, Expr::Ident(ident) if &*ident.sym == helper) {
+        return None;
+    }
+    match args.as_slice() {
+        [ExprOrSpread { spread: None, expr }] => match &**expr {
+            Expr::Lit(Lit::Str(s)) => Some(s),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Local name of `name` imported from the module (from any module if None), e.g. by the previous run
+ */
+fn imported_as(items: &[ModuleItem], name: &str, module: Option<&str>) -> Option<JsWord> {
+    items.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+            if module.is_none_or(|module| &*import.src.value == module) =>
+        {
+            import
+                .specifiers
+                .iter()
+                .find_map(|specifier| match specifier {
+                    ImportSpecifier::Named(named) => {
+                        let imported = match &named.imported {
+                            Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                            Some(ModuleExportName::Str(s)) => &s.value,
+                            None => &named.local.sym,
+                        };
+                        (&**imported == name).then(|| named.local.sym.clone())
+                    }
+                    _ => None,
+                })
+        }
+        _ => None,
+    })
+}
+
+/**
+ * Insert `import { testId as _testId } from "<module>";` after the directives ("use client")
+ * with the names which are not imported yet
+ */
+pub fn insert_runtime_import(
+    items: &mut Vec<ModuleItem>,
+    names: &[&str],
+    locals: &RuntimeNames,
+    module: &str,
+) {
+    let specifiers: Vec<ImportSpecifier> = names
+        .iter()
+        .filter(|name| imported_as(items, name, Some(module)).is_none())
+        .map(|name| {
+            let local = locals.local(name);
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: Ident::new(local.clone(), DUMMY_SP),
+                imported: (&**local != *name)
+                    .then(|| ModuleExportName::Ident(Ident::new((*name).into(), DUMMY_SP))),
+                is_type_only: false,
+            })
+        })
+        .collect();
+    if specifiers.is_empty() {
+        return;
+    }
+    let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers,
+        src: str_lit(module),
+        type_only: false,
+        asserts: None,
+    }));
+    let directives = items
+        .iter()
+        .take_while(|item| {
+            matches!(
+                item,
+                ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))
+                    if matches!(&**expr, Expr::Lit(Lit::Str(_)))
+            )
+        })
+        .count();
+    items.insert(directives, import);
+}
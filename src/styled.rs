@@ -0,0 +1,222 @@
+use serde::Deserialize;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        CallExpr, Callee, Expr, ExprOrSpread, Ident, Invalid, KeyValueProp, MemberExpr, MemberProp,
+        ObjectLit, Prop, PropName, PropOrSpread, SpreadElement,
+    },
+};
+
+use crate::{
+    assignment::{member, str_lit, Assignment},
+    element_call::prop_key_is,
+};
+
+/**
+ * Test ids of styled primitives (`const Wrapper = styled.div`...``), which render
+ * their element without any JSX in the code
+ *
+ * off:           left alone
+ * attrs:         styled.div.attrs({ "data-testid": "wrapper" })`...` (styled-components)
+ * default-props: Wrapper.defaultProps = { "data-testid": "wrapper", ...Wrapper.defaultProps }
+ *                after the declaration (@emotion/styled, which has no attrs())
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Styled {
+    #[default]
+    Off,
+    Attrs,
+    DefaultProps,
+}
+
+/**
+ * Tag (or callee for object styles) of a styled declaration
+ *
+ * styled.div`...`
+ * styled(Button)`...`
+ * styled("div")`...`
+ * styled.div.attrs({ ... })`...`
+ * styled.div.withConfig({ ... })`...`
+ * styled.div({ ... })
+ */
+pub fn styled_tag(init: &mut Expr) -> Option<&mut Box<Expr>> {
+    let tag = match init {
+        Expr::TaggedTpl(tagged_tpl) => &mut tagged_tpl.tag,
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            ..
+        }) => callee,
+        _ => return None,
+    };
+    is_styled(tag).then_some(tag)
+}
+
+/**
+ * Whether the expression creates a styled component
+ */
+fn is_styled(expr: &Expr) -> bool {
+    match expr {
+        // styled.div
+        Expr::Member(MemberExpr { obj, .. }) => is_styled_ident(obj),
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            ..
+        }) => match &**callee {
+            // styled(Button)
+            callee if is_styled_ident(callee) => true,
+            // styled.div.attrs(...), styled.div.withConfig(...)
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(method),
+                ..
+            }) if &*method.sym == "attrs" || &*method.sym == "withConfig" => is_styled(obj),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn is_styled_ident(expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(ident) if &*ident.sym == "styled")
+}
+
+fn test_id_prop(attr_name: &str, value: Expr) -> PropOrSpread {
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Str(str_lit(attr_name)),
+        value: Box::new(value),
+    })))
+}
+
+/**
+ * Add the attribute to the attrs() of the declaration, unless an object of them already sets it
+ * styled.div                           -> styled.div.attrs({ "data-testid": "wrapper" })
+ * styled.input.attrs({ type: "text" }) -> styled.input.attrs({ "data-testid": "input", type: "text" })
+ * styled.input.attrs(props => ...)     -> styled.input.attrs({ "data-testid": "input" }).attrs(props => ...)
+ */
+pub fn add_attrs(tag: &mut Box<Expr>, attr_name: &str, value: Expr) {
+    let attrs = attrs_args(tag);
+    let sets_attr = attrs.iter().any(|arg| match arg {
+        Some(Expr::Object(object)) => object.props.iter().any(|prop| match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::KeyValue(KeyValueProp { key, .. }) => prop_key_is(key, attr_name),
+                Prop::Shorthand(ident) => &*ident.sym == attr_name,
+                _ => false,
+            },
+            PropOrSpread::Spread(_) => false,
+        }),
+        _ => false,
+    });
+    if sets_attr {
+        return;
+    }
+    if attrs.is_empty() {
+        chain_attrs(tag, attr_name, value);
+    } else {
+        add_to_first_attrs(tag, attr_name, value);
+    }
+}
+
+/**
+ * First arguments of the attrs() calls of the chain, the first call first
+ * (None for a spread or no argument)
+ */
+fn attrs_args(expr: &Expr) -> Vec<Option<&Expr>> {
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        args,
+        ..
+    }) = expr
+    else {
+        return vec![];
+    };
+    let Expr::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(method),
+        ..
+    }) = &**callee
+    else {
+        return vec![];
+    };
+    let mut attrs = attrs_args(obj);
+    if &*method.sym == "attrs" {
+        attrs.push(
+            args.first()
+                .filter(|arg| arg.spread.is_none())
+                .map(|arg| &*arg.expr),
+        );
+    }
+    attrs
+}
+
+/**
+ * Add the attribute first in the first attrs() of the chain, so that the later ones override it
+ * as in the code: into its object, or into attrs() chained before a function
+ */
+fn add_to_first_attrs(expr: &mut Expr, attr_name: &str, value: Expr) {
+    let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        args,
+        ..
+    }) = expr
+    else {
+        return;
+    };
+    let Expr::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(method),
+        ..
+    }) = &mut **callee
+    else {
+        return;
+    };
+    if &*method.sym != "attrs" || !attrs_args(obj).is_empty() {
+        return add_to_first_attrs(obj, attr_name, value);
+    }
+    match args.first_mut().map(|arg| (arg.spread, &mut *arg.expr)) {
+        Some((None, Expr::Object(object))) => {
+            object.props.insert(0, test_id_prop(attr_name, value))
+        }
+        _ => chain_attrs(obj, attr_name, value),
+    }
+}
+
+/**
+ * styled.div -> styled.div.attrs({ "data-testid": "wrapper" })
+ */
+fn chain_attrs(tag: &mut Box<Expr>, attr_name: &str, value: Expr) {
+    let styled = std::mem::replace(&mut **tag, Expr::Invalid(Invalid { span: DUMMY_SP }));
+    **tag = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(member(styled, "attrs")),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: vec![test_id_prop(attr_name, value)],
+            })),
+        }],
+        type_args: None,
+    });
+}
+
+/**
+ * Wrapper.defaultProps = { "data-testid": "wrapper", ...Wrapper.defaultProps };
+ */
+pub fn default_props(object: Ident, attr_name: &str, value: Expr) -> Assignment {
+    let default_props = member(Expr::Ident(object.clone()), "defaultProps");
+    Assignment {
+        object,
+        prop: "defaultProps",
+        value: Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: vec![
+                test_id_prop(attr_name, value),
+                PropOrSpread::Spread(SpreadElement {
+                    dot3_token: DUMMY_SP,
+                    expr: default_props,
+                }),
+            ],
+        }),
+    }
+}
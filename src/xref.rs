@@ -0,0 +1,438 @@
+use std::path::Path;
+
+use serde_json::{json, Value};
+use swc_core::{
+    common::Span,
+    ecma::{
+        ast::{CallExpr, Callee, Expr, ExprOrSpread, Lit, MemberProp, Str, Tpl},
+        visit::{Visit, VisitWith},
+    },
+};
+
+use crate::{
+    diff::{dir_ids, source_files, TestId},
+    runner::{parse_file, ParsedFile},
+    Config,
+};
+
+/**
+ * Queries of Testing Library, Playwright and cypress-testing-library which take an id
+ */
+const TEST_ID_QUERIES: &[&str] = &[
+    "getByTestId",
+    "getAllByTestId",
+    "queryByTestId",
+    "queryAllByTestId",
+    "findByTestId",
+    "findAllByTestId",
+];
+
+/**
+ * An id which a test looks up, as a pattern where `*` matches anything
+ * getByTestId("user-card")         -> "user-card"
+ * getByTestId(`row-${i}`)          -> "row-*"
+ * '[data-testid^="user-"]'         -> "user-*"
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    pub pattern: String,
+    /// relative to the root of the tests, with `/` separators
+    pub file: String,
+    pub line: usize,
+}
+
+impl Selector {
+    fn to_json(&self) -> Value {
+        json!({ "selector": self.pattern, "file": self.file, "line": self.line })
+    }
+
+    pub fn matches(&self, id: &str) -> bool {
+        let mut parts = self.pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = id.strip_prefix(first) else {
+            return false;
+        };
+        let parts: Vec<&str> = parts.collect();
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty();
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+}
+
+/**
+ * Patterns of the attribute selectors in CSS selector text, as passed to cy.get or page.locator
+ * [data-testid="user-card"] -> "user-card"
+ * [data-testid^=user-]      -> "user-*"
+ * [data-testid$='-button']  -> "*-button"
+ * [data-testid*="card" i]   -> "*card*"
+ */
+fn attribute_selectors(text: &str, attr_name: &str) -> Vec<String> {
+    let needle = format!("[{}", attr_name);
+    let mut patterns = vec![];
+    for (start, _) in text.match_indices(&needle) {
+        let rest = text[start + needle.len()..].trim_start();
+        let (operator, rest) = match rest.find('=') {
+            Some(index @ (0 | 1)) => (&rest[..index], rest[index + 1..].trim_start()),
+            _ => continue,
+        };
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next(),
+            _ => rest.split([']', ' ']).next(),
+        };
+        let Some(value) = value.filter(|value| !value.is_empty()) else {
+            continue;
+        };
+        patterns.push(match operator {
+            "" | "~" => value.to_string(),
+            "^" | "|" => format!("{}*", value),
+            "$" => format!("*{}", value),
+            "*" => format!("*{}*", value),
+            _ => continue,
+        });
+    }
+    patterns
+}
+
+/**
+ * Pattern of a template literal, with `*` for the expressions
+ */
+fn tpl_pattern(tpl: &Tpl) -> String {
+    tpl.quasis
+        .iter()
+        .map(|quasi| quasi.cooked.as_deref().unwrap_or(&*quasi.raw))
+        .collect::<Vec<_>>()
+        .join("*")
+}
+
+fn callee_name(callee: &Callee) -> Option<&str> {
+    let Callee::Expr(expr) = callee else {
+        return None;
+    };
+    match &**expr {
+        Expr::Ident(ident) => Some(&*ident.sym),
+        Expr::Member(member) => match &member.prop {
+            MemberProp::Ident(ident) => Some(&*ident.sym),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Find the ids which tests look up: arguments of the test id queries
+ * and attribute selectors in any string
+ */
+struct SelectorCollector<'a> {
+    attr_name: &'a str,
+    selectors: Vec<(String, Span)>,
+}
+
+impl Visit for SelectorCollector<'_> {
+    fn visit_call_expr(&mut self, n: &CallExpr) {
+        if callee_name(&n.callee).is_some_and(|name| TEST_ID_QUERIES.contains(&name)) {
+            // regular expressions are left out, as they would need a regex engine to compare
+            if let Some(ExprOrSpread { spread: None, expr }) = n.args.first() {
+                match &**expr {
+                    Expr::Lit(Lit::Str(s)) => self.selectors.push((s.value.to_string(), s.span)),
+                    Expr::Tpl(tpl) => self.selectors.push((tpl_pattern(tpl), tpl.span)),
+                    _ => (),
+                }
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_str(&mut self, n: &Str) {
+        for pattern in attribute_selectors(&n.value, self.attr_name) {
+            self.selectors.push((pattern, n.span));
+        }
+    }
+
+    fn visit_tpl(&mut self, n: &Tpl) {
+        for pattern in attribute_selectors(&tpl_pattern(n), self.attr_name) {
+            self.selectors.push((pattern, n.span));
+        }
+        n.visit_children_with(self);
+    }
+}
+
+/**
+ * Selectors of the test file in the order of the code
+ */
+pub fn collect_selectors(file: &ParsedFile, attr_name: &str, relative_path: &str) -> Vec<Selector> {
+    let mut collector = SelectorCollector {
+        attr_name,
+        selectors: vec![],
+    };
+    file.program.visit_with(&mut collector);
+    collector
+        .selectors
+        .into_iter()
+        .map(|(pattern, span)| Selector {
+            pattern,
+            file: relative_path.to_string(),
+            line: file.cm.lookup_char_pos(span.lo).line,
+        })
+        .collect()
+}
+
+/**
+ * Selectors of every test file under the directory, and the errors of the files which failed to parse and were skipped
+ */
+pub fn tests_selectors(
+    root: &Path,
+    attr_name: &str,
+) -> Result<(Vec<Selector>, Vec<String>), String> {
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("{}: {}", root.display(), err))?;
+    let mut files = vec![];
+    source_files(&root, &mut files)?;
+
+    let mut selectors = vec![];
+    let mut errors = vec![];
+    for path in files.iter() {
+        let file = match parse_file(path) {
+            Ok(file) => file,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        let relative_path = path
+            .strip_prefix(&root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        selectors.extend(collect_selectors(&file, attr_name, &relative_path));
+    }
+    Ok((selectors, errors))
+}
+
+/**
+ * Selectors without an id in the app, and ids which no test looks up
+ */
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CrossReference {
+    pub missing: Vec<Selector>,
+    pub unused: Vec<TestId>,
+}
+
+pub fn cross_reference(ids: &[TestId], selectors: &[Selector]) -> CrossReference {
+    CrossReference {
+        missing: selectors
+            .iter()
+            .filter(|selector| !ids.iter().any(|id| selector.matches(&id.id)))
+            .cloned()
+            .collect(),
+        unused: ids
+            .iter()
+            .filter(|id| !selectors.iter().any(|selector| selector.matches(&id.id)))
+            .cloned()
+            .collect(),
+    }
+}
+
+/**
+ * Cross-reference the ids of the app with the selectors of the tests, and return the errors of the files which were skipped.
+ * The app is transformed as a part of the project at root, which rootDir and the lockfile are resolved against.
+ * Test files inside the app are not part of it, so their ids are left out.
+ */
+pub fn tree_cross_reference(
+    root: &Path,
+    app: &Path,
+    tests: &Path,
+    config: &Config,
+) -> Result<(CrossReference, Vec<String>), String> {
+    let attr_name = if config.attr_name.is_empty() {
+        config.preset.attr_name()
+    } else {
+        &config.attr_name
+    };
+    let (selectors, mut errors) = tests_selectors(tests, attr_name)?;
+
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("{}: {}", root.display(), err))?;
+    let tests_root = tests
+        .canonicalize()
+        .map_err(|err| format!("{}: {}", tests.display(), err))?;
+    let tree = dir_ids(&root, app, config)?;
+    errors.extend(tree.errors);
+    let ids: Vec<TestId> = (tree.ids.into_iter())
+        .filter(|id| !root.join(&id.file).starts_with(&tests_root))
+        .collect();
+
+    Ok((cross_reference(&ids, &selectors), errors))
+}
+
+impl CrossReference {
+    /**
+     * Markdown to post in a pull request
+     */
+    pub fn to_markdown(&self) -> String {
+        let mut report = format!(
+            "### Test id selectors\n{} missing, {} unused\n",
+            self.missing.len(),
+            self.unused.len()
+        );
+        if self.missing.is_empty() && self.unused.is_empty() {
+            return report;
+        }
+
+        report.push_str("\n| problem | id | file |\n| --- | --- | --- |\n");
+        for selector in self.missing.iter() {
+            report.push_str(&format!(
+                "| missing | `{}` | {}:{} |\n",
+                selector.pattern, selector.file, selector.line
+            ));
+        }
+        for id in self.unused.iter() {
+            report.push_str(&format!(
+                "| unused | `{}` | {}:{} |\n",
+                id.id, id.file, id.line
+            ));
+        }
+        report
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "missing": self.missing.iter().map(Selector::to_json).collect::<Vec<_>>(),
+            "unused": self.unused.iter().map(TestId::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::runner::parse_source;
+
+    fn selectors_of(src: &str) -> Vec<(String, usize)> {
+        let file = parse_source(Path::new("e2e/cards.spec.ts"), src.to_string()).unwrap();
+        collect_selectors(&file, "data-testid", "e2e/cards.spec.ts")
+            .into_iter()
+            .map(|selector| (selector.pattern, selector.line))
+            .collect()
+    }
+
+    fn selector(pattern: &str) -> Selector {
+        Selector {
+            pattern: pattern.to_string(),
+            file: "e2e/cards.spec.ts".to_string(),
+            line: 1,
+        }
+    }
+
+    fn test_id(id: &str) -> TestId {
+        TestId {
+            id: id.to_string(),
+            file: "src/Cards.tsx".to_string(),
+            line: 1,
+        }
+    }
+
+    #[test]
+    fn collects_selectors() {
+        let src = r#"test("cards", async ({ page }) => {
+  await page.getByTestId("user-card").click();
+  screen.getAllByTestId(`row-${index}`);
+  cy.get('[data-testid="avatar"] > img');
+  cy.get("[data-testid^=nav-]").find("[data-testid$='-link' i]");
+  page.locator(`[data-testid*="card"]`);
+  screen.getByTestId(/button/);
+  cy.get("[data-testid]");
+});
+"#;
+        assert_eq!(
+            selectors_of(src),
+            vec![
+                ("user-card".to_string(), 2),
+                ("row-*".to_string(), 3),
+                ("avatar".to_string(), 4),
+                ("nav-*".to_string(), 5),
+                ("*-link".to_string(), 5),
+                ("*card*".to_string(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(selector("user-card").matches("user-card"));
+        assert!(!selector("user-card").matches("user-card-avatar"));
+        assert!(selector("row-*").matches("row-1"));
+        assert!(selector("*-link").matches("nav-link"));
+        assert!(selector("*card*").matches("user-card__avatar"));
+        assert!(selector("list__*__item").matches("list__row__item"));
+        assert!(!selector("list__*__item").matches("list__item"));
+    }
+
+    #[test]
+    fn missing_and_unused() {
+        let ids = vec![test_id("user-card"), test_id("row-1"), test_id("footer")];
+        let selectors = vec![selector("user-card"), selector("row-*"), selector("header")];
+        let xref = cross_reference(&ids, &selectors);
+        assert_eq!(
+            xref,
+            CrossReference {
+                missing: vec![selector("header")],
+                unused: vec![test_id("footer")],
+            }
+        );
+        assert_eq!(
+            xref.to_markdown(),
+            "### Test id selectors\n1 missing, 1 unused\n\n| problem | id | file |\n| --- | --- | --- |\n\
+             | missing | `header` | e2e/cards.spec.ts:1 |\n\
+             | unused | `footer` | src/Cards.tsx:1 |\n"
+        );
+        assert_eq!(
+            xref.to_json()["missing"][0],
+            json!({ "selector": "header", "file": "e2e/cards.spec.ts", "line": 1 })
+        );
+    }
+
+    #[test]
+    fn app_of_a_project() {
+        let root =
+            std::env::temp_dir().join(format!("swc-test-plugin-xref-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("e2e")).unwrap();
+        // the locked id of App is only found with the lockfile and paths relative to the project
+        fs::write(
+            root.join("testids.lock.json"),
+            r#"{ "src/App.tsx": { "App": "home" } }"#,
+        )
+        .unwrap();
+        fs::write(root.join("src/App.tsx"), "const App = () => (<main />);\n").unwrap();
+        fs::write(
+            root.join("e2e/app.spec.ts"),
+            "page.getByTestId(\"home\");\n",
+        )
+        .unwrap();
+        fs::write(root.join("e2e/broken.spec.ts"), "page.getByTestId(;\n").unwrap();
+
+        let config = Config {
+            attr_name: "data-testid".to_string(),
+            lockfile: "testids.lock.json".to_string(),
+            ..Default::default()
+        };
+        let (xref, errors) =
+            tree_cross_reference(&root, Path::new("src"), &root.join("e2e"), &config).unwrap();
+        assert_eq!(xref, CrossReference::default());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("broken.spec.ts"), "{:?}", errors);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
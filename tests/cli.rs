@@ -0,0 +1,143 @@
+//! Tests of the native binary
+//!
+//!   cargo test --features cli --test cli
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_swc-test-plugin"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/**
+ * Directory of the project of a test, with a config and src/App.tsx
+ */
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "swc-test-plugin-cli-{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(dir.join("src/stories")).unwrap();
+    fs::write(
+        dir.join("config.json"),
+        r#"{ "attrName": "data-testid", "ignoreFiles": ["stories"], "ignoreComponents": [] }"#,
+    )
+    .unwrap();
+    fs::write(dir.join("src/App.tsx"), "const App = () => (<main />);\n").unwrap();
+    dir
+}
+
+#[test]
+fn prints_the_output() {
+    let dir = project("print");
+    let output = run(&dir, &["--config", "config.json", "src/App.tsx"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains(r#"<main data-testid="app"/>"#),
+        "{}",
+        stdout(&output)
+    );
+    // the file is left as it is
+    assert_eq!(
+        fs::read_to_string(dir.join("src/App.tsx")).unwrap(),
+        "const App = () => (<main />);\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn write() {
+    let dir = project("write");
+    let output = run(&dir, &["--config", "config.json", "--write", "src/App.tsx"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+    let written = fs::read_to_string(dir.join("src/App.tsx")).unwrap();
+    assert!(written.contains(r#"data-testid="app""#), "{}", written);
+
+    // ignored files are reported and left as they are
+    fs::write(
+        dir.join("src/stories/App.stories.tsx"),
+        "const Story = () => (<App />);\n",
+    )
+    .unwrap();
+    let output = run(
+        &dir,
+        &[
+            "--config",
+            "config.json",
+            "--write",
+            "src/stories/App.stories.tsx",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("ignored by ignoreFiles"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("src/stories/App.stories.tsx")).unwrap(),
+        "const Story = () => (<App />);\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn exit_codes() {
+    let dir = project("exit");
+
+    // invalid arguments and configs
+    let output = run(&dir, &["--fix", "src/App.tsx"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("unknown option: --fix"),
+        "{}",
+        stderr(&output)
+    );
+    let output = run(&dir, &["--config", "missing.json", "src/App.tsx"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    // a file which fails to parse
+    fs::write(
+        dir.join("src/Broken.tsx"),
+        "const Broken = () => (<main>;\n",
+    )
+    .unwrap();
+    let output = run(&dir, &["--config", "config.json", "src/Broken.tsx"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).starts_with("src/Broken.tsx:"),
+        "{}",
+        stderr(&output)
+    );
+
+    // --check fails with the missing attributes
+    let output = run(&dir, &["--config", "config.json", "--check", "src/App.tsx"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("src/App.tsx:1:"),
+        "{}",
+        stderr(&output)
+    );
+    run(&dir, &["--config", "config.json", "--write", "src/App.tsx"]);
+    let output = run(&dir, &["--config", "config.json", "--check", "src/App.tsx"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
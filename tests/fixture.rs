@@ -0,0 +1,112 @@
+//! Fixture tests
+//!
+//! Each directory under tests/fixtures is one case:
+//!   input.tsx    code before the transform
+//!   config.json  plugin config (optional, merged into the default config below, null removes a key)
+//!   output.tsx   expected code after the transform
+//!   output.stderr expected problems in `mode: "check"`
+//!
+//! Run with `UPDATE=1 cargo test --test fixture` to regenerate output.tsx and output.stderr.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use swc_core::{
+    common::{errors::HANDLER, FileName},
+    ecma::{
+        ast::Module,
+        parser::{Syntax, TsConfig},
+        transforms::testing::{test_fixture, test_fixture_allowing_error, Tester},
+        visit::{Fold, VisitMutWith},
+    },
+    testing::fixture,
+};
+use swc_test_plugin::{Config, Mode, TransformVisitor};
+
+/**
+ * Same as make_test_visitor() of the inline tests
+ */
+fn default_config() -> Value {
+    json!({
+        "attrName": "data-testid",
+        "ignoreFiles": [],
+        "ignoreComponents": [],
+    })
+}
+
+fn read_config(dir: &Path) -> Config {
+    let mut plugin_config = default_config();
+    let path = dir.join("config.json");
+    if path.exists() {
+        let text = std::fs::read_to_string(&path).unwrap();
+        let overrides: Value =
+            serde_json::from_str(&text).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        let Value::Object(overrides) = overrides else {
+            panic!("{}: config should be an object", path.display());
+        };
+        // null removes a key of the default config
+        for (key, value) in overrides {
+            match (&mut plugin_config, value) {
+                (Value::Object(keys), Value::Null) => {
+                    keys.remove(&key);
+                }
+                (_, value) => plugin_config[key] = value,
+            }
+        }
+    }
+    Config::from_value(&plugin_config).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+/**
+ * Run the visitor like process_transform,
+ * and report the problems found in `mode: "check"` to the handler of the tester
+ */
+struct FixturePass {
+    visitor: Option<TransformVisitor>,
+}
+
+impl Fold for FixturePass {
+    fn fold_module(&mut self, mut module: Module) -> Module {
+        let Some(visitor) = &mut self.visitor else {
+            // ignored by ignoreFiles
+            return module;
+        };
+        module.visit_mut_with(visitor);
+
+        let diagnostics = visitor.take_diagnostics();
+        HANDLER.with(|handler| {
+            for diagnostic in diagnostics.iter() {
+                diagnostic.emit(handler);
+            }
+        });
+        module
+    }
+}
+
+#[fixture("tests/fixtures/**/input.tsx")]
+fn fixture(input: PathBuf) {
+    let dir = input.parent().unwrap();
+    let output = dir.join("output.tsx");
+    let config = read_config(dir);
+    let filename = FileName::Real(input.clone());
+
+    let syntax = Syntax::Typescript(TsConfig {
+        tsx: true,
+        ..Default::default()
+    });
+    let tr = |_: &mut Tester| {
+        let visitor = (!config.is_ignored_file(&filename)).then(|| {
+            let mut visitor = TransformVisitor::new();
+            visitor.set_config(&config, filename.clone());
+            visitor
+        });
+        FixturePass { visitor }
+    };
+
+    // problems are compared with output.stderr
+    if config.mode == Mode::Check {
+        test_fixture_allowing_error(syntax, &tr, &input, &output);
+    } else {
+        test_fixture(syntax, &tr, &input, &output);
+    }
+}
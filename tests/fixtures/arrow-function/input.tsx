@@ -0,0 +1,17 @@
+import { User } from './types/user';
+
+type Props = {
+  user: User
+}
+
+export const UserComponent = ({ user }: Props) => {
+  return (
+    <User user={user}>
+      <img src="sample.png" lazy-load="false" />
+    </User>
+  )
+}
+
+const WithoutReturn = () => (
+  <img src="sample.png" lazy-load="false" />
+)
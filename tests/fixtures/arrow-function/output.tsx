@@ -0,0 +1,12 @@
+import { User } from './types/user';
+type Props = {
+    user: User;
+};
+export const UserComponent = ({ user  }: Props)=>{
+    return <User user={user} data-testid="user-component">
+
+      <img src="sample.png" lazy-load="false"/>
+
+    </User>;
+};
+const WithoutReturn = ()=><img src="sample.png" lazy-load="true" data-testid="without-return"/>;
@@ -0,0 +1,9 @@
+function Missing() {
+    return <div />;
+}
+function Matched() {
+    return <div data-testid="matched"/>;
+}
+function Mismatched() {
+    return <div data-testid="other-name"/>;
+}
@@ -0,0 +1,12 @@
+function Div() {
+    return <div data-testid="div"/>;
+}
+function Nested() {
+    return <div data-testid="nested">
+
+      hello
+
+      <div >world</div>
+
+    </div>;
+}
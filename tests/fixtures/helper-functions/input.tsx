@@ -0,0 +1,12 @@
+export function UserList({ users }) {
+  const renderUser = (user) => {
+    return <li>{user.name}</li>;
+  };
+
+  return <ul>{users.map(renderUser)}</ul>;
+}
+
+// not declared in a component
+function renderFooter() {
+  return <footer />;
+}
@@ -0,0 +1,10 @@
+export function UserList({ users  }) {
+    const renderUser = (user)=>{
+        return <li data-testid="user-list">{user.name}</li>;
+    };
+    return <ul data-testid="user-list">{users.map(renderUser)}</ul>;
+}
+// not declared in a component
+function renderFooter() {
+    return <footer />;
+}
@@ -0,0 +1,3 @@
+function UserCard() {
+  return <div />
+}
@@ -0,0 +1,9 @@
+const Layout = ({ children }) => (
+  <main>{children}</main>
+)
+
+const Page = () => (
+  <Layout>
+    <h1>page</h1>
+  </Layout>
+)
@@ -0,0 +1,6 @@
+const Layout = ({ children  })=><main >{children}</main>;
+const Page = ()=><Layout data-testid="page">
+
+    <h1 >page</h1>
+
+  </Layout>;
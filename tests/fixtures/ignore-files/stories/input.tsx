@@ -0,0 +1,3 @@
+const Story = () => (
+  <div>story</div>
+)
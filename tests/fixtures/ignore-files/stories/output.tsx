@@ -0,0 +1 @@
+const Story = ()=><div >story</div>;
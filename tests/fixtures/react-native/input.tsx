@@ -0,0 +1,5 @@
+const UserCard = () => (
+  <View>
+    <Text>name</Text>
+  </View>
+)
@@ -0,0 +1,5 @@
+const UserCard = ()=><View testID="userCard">
+
+    <Text >name</Text>
+
+  </View>;
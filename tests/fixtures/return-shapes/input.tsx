@@ -0,0 +1,29 @@
+// none of the returned elements are wrapped in parentheses
+export const Bare = () => <div />;
+
+export const Ternary = ({ isOpen }) => isOpen ? <dialog /> : <button />;
+
+export const LogicalAnd = ({ isOpen }) => isOpen && <dialog />;
+
+export const LogicalOr = ({ error }) => error || <form />;
+
+export const Fragment = () => <><h1 /><p /></>;
+
+export function NestedIf({ user }) {
+  if (!user) {
+    return <Spinner />;
+  }
+  return <section />;
+}
+
+export function NestedSwitch({ status }) {
+  switch (status) {
+    case "loading":
+      return <Spinner />;
+    default:
+      return null;
+  }
+}
+
+// returns no JSX
+export const Label = ({ text }) => text.toUpperCase();
@@ -0,0 +1,22 @@
+// none of the returned elements are wrapped in parentheses
+export const Bare = ()=><div data-testid="bare"/>;
+export const Ternary = ({ isOpen  })=>isOpen ? <dialog data-testid="ternary"/> : <button data-testid="ternary"/>;
+export const LogicalAnd = ({ isOpen  })=>isOpen && <dialog data-testid="logical-and"/>;
+export const LogicalOr = ({ error  })=>error || <form data-testid="logical-or"/>;
+export const Fragment = ()=><><h1 data-testid="fragment"/><p /></>;
+export function NestedIf({ user  }) {
+    if (!user) {
+        return <Spinner data-testid="nested-if"/>;
+    }
+    return <section data-testid="nested-if"/>;
+}
+export function NestedSwitch({ status  }) {
+    switch(status){
+        case "loading":
+            return <Spinner data-testid="nested-switch"/>;
+        default:
+            return null;
+    }
+}
+// returns no JSX
+export const Label = ({ text  })=>text.toUpperCase();
@@ -0,0 +1,312 @@
+//! Property tests
+//!
+//! Random JSX trees wrapped in random component shapes are run through TransformVisitor.
+//! The transform should
+//!   - never panic
+//!   - never change code outside of JSX
+//!   - always print code which parses again
+//!   - change nothing when it runs again on the re-parsed output
+//!
+//! Failing cases are saved in tests/proptest.proptest-regressions.
+//! Set PROPTEST_CASES to run more cases than the default.
+
+use proptest::prelude::*;
+use swc_core::{
+    common::{EqIgnoreSpan, FileName, DUMMY_SP},
+    ecma::{
+        ast::{Expr, Lit, Module, Null},
+        parser::{Syntax, TsConfig},
+        transforms::testing::Tester,
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use swc_test_plugin::{
+    ComponentDetection, Config, HelperFunctions, Mode, Preset, RenderProps, TransformVisitor,
+};
+
+fn syntax() -> Syntax {
+    Syntax::Typescript(TsConfig {
+        tsx: true,
+        ..Default::default()
+    })
+}
+
+/**
+ * Host elements, components, member and namespaced names
+ */
+fn element_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("div".to_string()),
+        Just("span".to_string()),
+        Just("img".to_string()),
+        Just("View".to_string()),
+        "[A-Z][a-z]{0,5}",
+        Just("Foo.Bar".to_string()),
+        Just("React.Fragment".to_string()),
+        Just("Context.Provider".to_string()),
+        Just("svg:rect".to_string()),
+    ]
+}
+
+fn component_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[A-Z][a-zA-Z]{0,8}",
+        // lower camel case and names with digits (a prefix keeps clear of keywords)
+        "render[A-Z0-9][a-zA-Z0-9]{0,6}",
+        Just("_Private".to_string()),
+        Just("UserCard".to_string()),
+    ]
+}
+
+fn attr() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(r#"className="box""#.to_string()),
+        Just(r#"data-testid="given""#.to_string()),
+        Just("data-testid={id}".to_string()),
+        Just(r#"testID="given""#.to_string()),
+        Just(r#"test:id="given""#.to_string()),
+        Just(r#"lazy-load="false""#.to_string()),
+        Just(r#"lazy-load="true""#.to_string()),
+        Just("{...props}".to_string()),
+        Just("disabled".to_string()),
+        Just("onClick={() => setOpen(true)}".to_string()),
+        Just("render={() => <Page />}".to_string()),
+        Just("xlink:href=\"#a\"".to_string()),
+    ]
+}
+
+fn attrs() -> impl Strategy<Value = String> {
+    prop::collection::vec(attr(), 0..4).prop_map(|attrs| {
+        attrs
+            .iter()
+            .map(|attr| format!(" {}", attr))
+            .collect::<String>()
+    })
+}
+
+/**
+ * JSX element or fragment with random children
+ */
+fn jsx() -> impl Strategy<Value = String> {
+    let leaf = (element_name(), attrs()).prop_map(|(name, attrs)| format!("<{}{} />", name, attrs));
+    leaf.prop_recursive(4, 24, 4, |inner| {
+        let child = prop_oneof![
+            inner.clone(),
+            Just("text".to_string()),
+            Just("{value}".to_string()),
+            Just("{cond && <b />}".to_string()),
+            inner
+                .clone()
+                .prop_map(|jsx| format!("{{items.map((item) => {})}}", jsx)),
+            inner
+                .clone()
+                .prop_map(|jsx| format!("{{(form) => ({})}}", jsx)),
+        ];
+        let children = prop::collection::vec(child, 0..4).prop_map(|children| children.concat());
+        prop_oneof![
+            (element_name(), attrs(), children.clone()).prop_map(
+                |(name, attrs, children)| format!("<{0}{1}>{2}</{0}>", name, attrs, children)
+            ),
+            children.prop_map(|children| format!("<>{}</>", children)),
+        ]
+    })
+}
+
+/**
+ * Component shapes which are supported or not supported by the visitor
+ */
+fn statement() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("function {}() {{ return {}; }}", name, jsx)),
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("export function {}() {{ return ({}); }}", name, jsx)),
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("const {} = () => ({});", name, jsx)),
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("const {} = () => {};", name, jsx)),
+        (component_name(), jsx()).prop_map(|(name, jsx)| {
+            format!(
+                "const {} = function () {{ const a = 1; return {}; }};",
+                name, jsx
+            )
+        }),
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("function {}() {{ {}; }}", name, jsx)),
+        (component_name(), jsx(), jsx()).prop_map(|(name, a, b)| {
+            format!(
+                "function {}() {{ if (cond) return {}; return {}; }}",
+                name, a, b
+            )
+        }),
+        (component_name(), component_name(), jsx()).prop_map(|(outer, inner, jsx)| {
+            format!(
+                "function {}() {{ const {} = () => ({}); return <{} />; }}",
+                outer, inner, jsx, inner
+            )
+        }),
+        jsx().prop_map(|jsx| format!("export default function () {{ return {}; }}", jsx)),
+        jsx().prop_map(|jsx| format!("const element = {};", jsx)),
+        jsx().prop_map(|jsx| format!("const routes = {{ Home: () => {} }};", jsx)),
+        (component_name(), jsx())
+            .prop_map(|(name, jsx)| format!("const map = {{ {}() {{ return {}; }} }};", name, jsx)),
+        jsx().prop_map(|jsx| format!("render({}, root);", jsx)),
+        // no JSX at all
+        Just("const n = 1 + 2;".to_string()),
+        Just("function helper(a: number) { return a * 2; }".to_string()),
+        Just("class Store { value = 'false'; }".to_string()),
+        (component_name(), jsx()).prop_map(|(name, jsx)| {
+            format!(
+                "function {}() {{ const [a] = useState(0); return {}; }}",
+                name, jsx
+            )
+        }),
+        Just("let a, b = 'lazy-load';".to_string()),
+    ]
+}
+
+fn program() -> impl Strategy<Value = String> {
+    prop::collection::vec(statement(), 1..5).prop_map(|stmts| stmts.join("\n"))
+}
+
+fn config() -> impl Strategy<Value = Config> {
+    let preset = prop_oneof![
+        Just(Preset::ReactDom),
+        Just(Preset::ReactNative),
+        Just(Preset::Preact),
+        Just(Preset::Solid),
+        Just(Preset::Vue),
+    ];
+    let mode = prop_oneof![Just(Mode::Transform), Just(Mode::Check)];
+    let id_template = prop_oneof![Just(""), Just("{component}"), Just("page-{component}-root")];
+    // the attribute of the preset, or one with a namespace
+    let attr_name = prop_oneof![Just(""), Just("test:id")];
+    let detection = (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(
+        |(pascal_case, returns_jsx, require_hooks)| ComponentDetection {
+            pascal_case,
+            returns_jsx,
+            require_hooks,
+        },
+    );
+    let helper_functions = prop_oneof![
+        Just(HelperFunctions::Skip),
+        Just(HelperFunctions::EnclosingComponent)
+    ];
+    let render_props = prop_oneof![
+        Just(RenderProps::Off),
+        Just(RenderProps::Index),
+        Just(RenderProps::AttrName)
+    ];
+    (
+        (preset, mode, id_template, attr_name),
+        (detection, helper_functions, render_props, any::<bool>()),
+    )
+        .prop_map(
+            |(
+                (preset, mode, id_template, attr_name),
+                (component_detection, helper_functions, render_props, ignore),
+            )| {
+                Config {
+                    attr_name: attr_name.to_string(),
+                    ignore_components: if ignore {
+                        vec!["\"UserCard\"".to_string()]
+                    } else {
+                        vec![]
+                    },
+                    preset,
+                    id_template: id_template.to_string(),
+                    mode,
+                    component_detection,
+                    helper_functions,
+                    render_props,
+                    ..Default::default()
+                }
+            },
+        )
+}
+
+/**
+ * Replace every JSX element and fragment with `null`
+ * so that only the code outside of JSX is compared
+ */
+struct StripJsx;
+
+impl VisitMut for StripJsx {
+    fn visit_mut_expr(&mut self, n: &mut Expr) {
+        if let Expr::JSXElement(_) | Expr::JSXFragment(_) = n {
+            *n = Expr::Lit(Lit::Null(Null { span: DUMMY_SP }));
+            return;
+        }
+        n.visit_mut_children_with(self);
+    }
+}
+
+fn run(src: &str, config: &Config) {
+    Tester::run(|tester| {
+        let mut original = tester.with_parser("input.tsx", syntax(), src, |p| p.parse_module())?;
+
+        let mut module: Module = original.clone();
+        let mut visitor = TransformVisitor::new();
+        visitor.set_config(config, FileName::Real("input.tsx".into()));
+        module.visit_mut_with(&mut visitor);
+        visitor.take_diagnostics();
+
+        let output = tester.print(&module, &tester.comments.clone());
+        let reparsed = tester.with_parser("output.tsx", syntax(), &output, |p| p.parse_module());
+        let Ok(reparsed) = reparsed else {
+            panic!(
+                "output does not parse\ninput:\n{}\noutput:\n{}",
+                src, output
+            );
+        };
+
+        let mut twice = reparsed.clone();
+        let mut visitor = TransformVisitor::new();
+        visitor.set_config(config, FileName::Real("output.tsx".into()));
+        twice.visit_mut_with(&mut visitor);
+        assert!(
+            twice.eq_ignore_span(&reparsed),
+            "second transform changed the output\ninput:\n{}\noutput:\n{}",
+            src,
+            output
+        );
+
+        original.visit_mut_with(&mut StripJsx);
+        module.visit_mut_with(&mut StripJsx);
+        assert!(
+            original.eq_ignore_span(&module),
+            "code outside of JSX changed\ninput:\n{}\noutput:\n{}",
+            src,
+            output
+        );
+        Ok(())
+    })
+}
+
+proptest! {
+    #[test]
+    fn transform_is_safe(src in program(), config in config()) {
+        run(&src, &config);
+    }
+}
+
+/**
+ * Cases which panicked before the visitor tracked the root element by scope
+ */
+#[test]
+fn member_and_namespaced_roots() {
+    let config = Config::default();
+    run(
+        "function Item() { return <Foo.Bar><div /></Foo.Bar>; }",
+        &config,
+    );
+    run("function Icon() { return <svg:rect />; }", &config);
+    run(
+        "const List = () => (<ul><ul><li /></ul><li /></ul>);",
+        &config,
+    );
+    run(
+        "const App = () => (<Theme.Provider value={1}><React.Fragment>x<main /></React.Fragment></Theme.Provider>);",
+        &config,
+    );
+}